@@ -0,0 +1,396 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::cancel::CancelToken;
+use crate::heuristics::{rotate_to_start, Construction, ConstructionHeuristic};
+use crate::local_search::{nearest_neighbours, LocalSearch};
+use crate::progress::{ProgressObserver, SearchProgress};
+use crate::rng::Rng;
+use crate::tsp_solver::{is_symmetric, tour_cost, ConvergencePoint};
+
+/// Closest cities an ant considers before falling back to all unvisited cities
+const CANDIDATES: usize = 15;
+
+/// ACS: chance of taking the best-looking edge instead of sampling
+const EXPLOITATION: f64 = 0.9;
+
+/// ACS: weight of the local pheromone update
+const LOCAL_DECAY: f64 = 0.1;
+
+/// MMAS: every this many iterations the best-so-far tour deposits instead of the iteration best
+const GLOBAL_BEST_EVERY: usize = 5;
+
+/// Pheromone update rule
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Colony {
+    /// Ant Colony System: greedy exploitation, local decay while walking, best-so-far deposit
+    AntColonySystem,
+    /// MAX-MIN Ant System: iteration-best deposit, pheromone clamped to [τmin, τmax]
+    MaxMin,
+}
+
+impl FromStr for Colony {
+    type Err = String;
+    
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "acs" => Ok(Colony::AntColonySystem),
+            "mmas" => Ok(Colony::MaxMin),
+            _ => Err(format!("unknown pheromone update rule '{}'", name)),
+        }
+    }
+}
+
+pub struct AntColony {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    colony: Colony,
+    seed: u64,
+    ants: usize,
+    iterations: usize,
+    alpha: f64,
+    beta: f64,
+    evaporation: f64,
+    local_search: bool,
+    time_limit: Option<Duration>,
+    cancel: CancelToken,
+    observer: Option<Arc<dyn ProgressObserver>>,
+    trace: Vec<ConvergencePoint>,
+}
+
+impl AntColony {
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            colony: Colony::AntColonySystem,
+            seed: 42,
+            ants: 20,
+            iterations: 500,
+            alpha: 1.0,
+            beta: 3.0,
+            evaporation: 0.1,
+            local_search: false,
+            time_limit: None,
+            cancel: CancelToken::new(),
+            observer: None,
+            trace: Vec::new(),
+        }
+    }
+    
+    pub fn with_colony(mut self, colony: Colony) -> Self {
+        self.colony = colony;
+        self
+    }
+    
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    
+    pub fn with_ants(mut self, ants: usize) -> Self {
+        self.ants = ants.max(1);
+        self
+    }
+    
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+    
+    /// Weight of pheromone (α) and of inverse distance (β) when choosing the next city
+    pub fn with_weights(mut self, alpha: f64, beta: f64) -> Self {
+        self.alpha = alpha;
+        self.beta = beta;
+        self
+    }
+    
+    /// Share of pheromone that evaporates per update (ρ)
+    pub fn with_evaporation(mut self, evaporation: f64) -> Self {
+        self.evaporation = evaporation.clamp(1e-6, 1.0);
+        self
+    }
+    
+    /// Improve every ant's tour with 2-opt / Or-opt before the pheromone update
+    pub fn with_local_search(mut self, enabled: bool) -> Self {
+        self.local_search = enabled;
+        self
+    }
+    
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+    
+    /// Stop the colony once `cancel` fires and return the best tour so far
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+    
+    /// Report iterations done and the best cost so far to `observer`
+    pub fn with_progress(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+    
+    /// Iteration-best and best-so-far cost per iteration over the last run
+    pub fn trace(&self) -> &[ConvergencePoint] {
+        &self.trace
+    }
+    
+    /// Let the colony build tours for the configured iterations (or time); returns the best tour
+    pub fn solve(&mut self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        self.trace.clear();
+        
+        if self.n < 4 {
+            let path: Vec<usize> = (0..self.n).collect();
+            return Ok((tour_cost(&self.distance_matrix, &path), path));
+        }
+        
+        let n = self.n;
+        let d = &self.distance_matrix;
+        let symmetric = is_symmetric(d);
+        let candidates = nearest_neighbours(d, CANDIDATES);
+        let local_search = self.local_search.then(|| LocalSearch::new(d.clone()));
+        
+        // η^β tidak berubah selama run
+        let visibility: Vec<Vec<f64>> = d.iter()
+            .map(|row| row.iter().map(|&dist| (1.0 / dist.max(1e-10)).powf(self.beta)).collect())
+            .collect();
+        
+        let mut best_path = ConstructionHeuristic::new(d.clone(), Construction::NearestNeighbour)
+            .nearest_neighbour_from(0);
+        let mut best_cost = tour_cost(d, &best_path);
+        
+        let (initial, mut bounds) = match self.colony {
+            Colony::AntColonySystem => (1.0 / (n as f64 * best_cost), None),
+            Colony::MaxMin => {
+                let limits = self.pheromone_limits(best_cost);
+                (limits.1, Some(limits))
+            }
+        };
+        let mut pheromone = vec![vec![initial; n]; n];
+        
+        let mut rng = Rng::new(self.seed);
+        let start = Instant::now();
+        let reporter = SearchProgress::new(self.observer.clone(), self.iterations as u64);
+        
+        println!("  • {:?} with {} ants, α = {}, β = {}, ρ = {} (seed {})",
+                self.colony, self.ants, self.alpha, self.beta, self.evaporation, self.seed);
+        
+        for iteration in 1..=self.iterations {
+            if self.time_limit.is_some_and(|limit| start.elapsed() >= limit) || self.cancel.is_cancelled() {
+                break;
+            }
+            
+            let mut iteration_best = (f64::INFINITY, Vec::new());
+            for _ in 0..self.ants {
+                let mut path = self.walk(&mut pheromone, &visibility, &candidates, initial, symmetric, &mut rng);
+                let cost = match &local_search {
+                    Some(search) => search.improve_quietly(&mut path),
+                    None => tour_cost(d, &path),
+                };
+                
+                if cost < iteration_best.0 {
+                    iteration_best = (cost, path);
+                }
+            }
+            
+            if iteration_best.0 < best_cost - 1e-9 {
+                best_cost = iteration_best.0;
+                best_path = iteration_best.1.clone();
+                
+                if let Some(limits) = bounds.as_mut() {
+                    *limits = self.pheromone_limits(best_cost);
+                }
+                if verbose {
+                    println!("    • Iteration {}: new best {:.1}", iteration, best_cost);
+                }
+            }
+            
+            let rho = self.evaporation;
+            match bounds {
+                None => {
+                    // ACS: hanya edge tour terbaik yang menguap dan diperkuat
+                    let deposit = rho / best_cost;
+                    for (a, b) in edges(&best_path) {
+                        pheromone[a][b] = (1.0 - rho) * pheromone[a][b] + deposit;
+                        if symmetric {
+                            pheromone[b][a] = pheromone[a][b];
+                        }
+                    }
+                }
+                Some((tau_min, tau_max)) => {
+                    for row in pheromone.iter_mut() {
+                        for tau in row.iter_mut() {
+                            *tau *= 1.0 - rho;
+                        }
+                    }
+                    
+                    let (cost, path) = if iteration % GLOBAL_BEST_EVERY == 0 {
+                        (best_cost, &best_path)
+                    } else {
+                        (iteration_best.0, &iteration_best.1)
+                    };
+                    for (a, b) in edges(path) {
+                        pheromone[a][b] += 1.0 / cost;
+                        if symmetric {
+                            pheromone[b][a] += 1.0 / cost;
+                        }
+                    }
+                    
+                    for row in pheromone.iter_mut() {
+                        for tau in row.iter_mut() {
+                            *tau = tau.clamp(tau_min, tau_max);
+                        }
+                    }
+                }
+            }
+            
+            self.trace.push(ConvergencePoint { iteration, current: iteration_best.0, best: best_cost });
+            reporter.update(iteration as u64, best_cost);
+        }
+        
+        let path = rotate_to_start(best_path);
+        let cost = tour_cost(&self.distance_matrix, &path);
+        reporter.finish(self.trace.len() as u64, cost);
+        println!("  • {} iterations in {:.2?}", self.trace.len(), start.elapsed());
+        
+        Ok((cost, path))
+    }
+    
+    /// MMAS trail limits for the current best cost
+    fn pheromone_limits(&self, best_cost: f64) -> (f64, f64) {
+        let tau_max = 1.0 / (self.evaporation * best_cost);
+        (tau_max / (2.0 * self.n as f64), tau_max)
+    }
+    
+    /// One ant's tour from a random start city
+    fn walk(
+        &self,
+        pheromone: &mut [Vec<f64>],
+        visibility: &[Vec<f64>],
+        candidates: &[Vec<usize>],
+        initial: f64,
+        symmetric: bool,
+        rng: &mut Rng,
+    ) -> Vec<usize> {
+        let n = self.n;
+        let mut visited = vec![false; n];
+        let mut path = Vec::with_capacity(n);
+        let mut current = rng.below(n);
+        path.push(current);
+        visited[current] = true;
+        
+        let weight = |pheromone: &[Vec<f64>], from: usize, to: usize| {
+            pheromone[from][to].powf(self.alpha) * visibility[from][to]
+        };
+        
+        while path.len() < n {
+            let open: Vec<usize> = candidates[current].iter().copied().filter(|&c| !visited[c]).collect();
+            
+            let next = if open.is_empty() {
+                // Semua kandidat sudah dikunjungi: ambil kota belum dikunjungi dengan bobot terbesar
+                (0..n)
+                    .filter(|&c| !visited[c])
+                    .max_by(|&a, &b| weight(pheromone, current, a).total_cmp(&weight(pheromone, current, b)))
+                    .unwrap()
+            } else if self.colony == Colony::AntColonySystem && rng.next_f64() < EXPLOITATION {
+                *open.iter()
+                    .max_by(|&&a, &&b| weight(pheromone, current, a).total_cmp(&weight(pheromone, current, b)))
+                    .unwrap()
+            } else {
+                let weights: Vec<f64> = open.iter().map(|&c| weight(pheromone, current, c)).collect();
+                let mut pick = rng.next_f64() * weights.iter().sum::<f64>();
+                let mut chosen = open[open.len() - 1];
+                for (&city, &w) in open.iter().zip(&weights) {
+                    if pick < w {
+                        chosen = city;
+                        break;
+                    }
+                    pick -= w;
+                }
+                chosen
+            };
+            
+            if self.colony == Colony::AntColonySystem {
+                let tau = &mut pheromone[current][next];
+                *tau = (1.0 - LOCAL_DECAY) * *tau + LOCAL_DECAY * initial;
+                if symmetric {
+                    pheromone[next][current] = pheromone[current][next];
+                }
+            }
+            
+            path.push(next);
+            visited[next] = true;
+            current = next;
+        }
+        
+        path
+    }
+}
+
+/// Directed edges of a closed tour
+fn edges(path: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..path.len()).map(move |i| (path[i], path[(i + 1) % path.len()]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pseudo_random_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    // input/input3.txt (asymmetric)
+    fn warehouse_matrix() -> Vec<Vec<f64>> {
+        vec![
+            vec![0.0, 5.0, 8.0, 12.0, 15.0, 18.0, 20.0],
+            vec![6.0, 0.0, 4.0, 9.0, 13.0, 16.0, 18.0],
+            vec![9.0, 5.0, 0.0, 6.0, 10.0, 14.0, 16.0],
+            vec![13.0, 10.0, 7.0, 0.0, 8.0, 11.0, 14.0],
+            vec![16.0, 14.0, 11.0, 9.0, 0.0, 7.0, 10.0],
+            vec![19.0, 17.0, 15.0, 12.0, 8.0, 0.0, 6.0],
+            vec![22.0, 20.0, 18.0, 15.0, 12.0, 9.0, 0.0],
+        ]
+    }
+    
+    #[test]
+    fn test_both_colonies_solve_asymmetric_instance() {
+        let matrix = warehouse_matrix();
+        let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        
+        for colony in [Colony::AntColonySystem, Colony::MaxMin] {
+            let mut aco = AntColony::new(matrix.clone())
+                .with_colony(colony)
+                .with_seed(3)
+                .with_iterations(100);
+            let (cost, path) = aco.solve(false).unwrap();
+            
+            assert_eq!(cost, optimum, "{:?}", colony);
+            assert_eq!(path[0], 0);
+            assert_eq!(aco.trace().len(), 100);
+        }
+    }
+    
+    #[test]
+    fn test_seed_is_reproducible() {
+        let matrix = pseudo_random_matrix(25, false);
+        
+        let run = |seed| AntColony::new(matrix.clone())
+            .with_colony(Colony::MaxMin)
+            .with_seed(seed)
+            .with_iterations(30)
+            .with_local_search(true)
+            .solve(false)
+            .unwrap();
+        let (cost, path) = run(9);
+        
+        assert_eq!(run(9), (cost, path.clone()));
+        assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-9);
+    }
+}
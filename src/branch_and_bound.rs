@@ -0,0 +1,400 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::cancel::CancelToken;
+use crate::heuristics::{Construction, ConstructionHeuristic};
+use crate::local_search::LocalSearch;
+use crate::lower_bound::{penalised_tree, LowerBound};
+use crate::progress::{ProgressObserver, SearchProgress};
+use crate::tsp_solver::{is_symmetric, tour_cost};
+
+/// Bounds within this distance of the incumbent are pruned (absorbs float rounding)
+const EPSILON: f64 = 1e-9;
+
+/// Warm-started subgradient steps per search node
+const NODE_ITERATIONS: usize = 10;
+
+/// Search nodes between two progress reports (improved tours are reported at once)
+const REPORT_EVERY: usize = 1 << 10;
+
+/// Lower bound used at every node of the search tree
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    /// Row/column reduced cost matrix, works for asymmetric matrices
+    ReducedCost,
+    /// Spanning tree over the unvisited cities plus both path endpoints, with
+    /// Held-Karp node penalties from a root 1-tree ascent (symmetric only)
+    OneTree,
+}
+
+pub struct BranchAndBound {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    bound: Bound,
+    best_cost: f64,
+    best_path: Vec<usize>,
+    nodes: usize,
+    cancel: CancelToken,
+    progress: SearchProgress,
+    proven: bool,
+}
+
+impl BranchAndBound {
+    /// Picks the 1-tree bound for symmetric matrices and the reduced cost bound otherwise
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let bound = if is_symmetric(&distance_matrix) {
+            Bound::OneTree
+        } else {
+            Bound::ReducedCost
+        };
+        Self::with_bound(distance_matrix, bound)
+    }
+    
+    pub fn with_bound(distance_matrix: Vec<Vec<f64>>, bound: Bound) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            bound,
+            best_cost: f64::INFINITY,
+            best_path: Vec::new(),
+            nodes: 0,
+            cancel: CancelToken::new(),
+            progress: SearchProgress::new(None, 0),
+            proven: true,
+        }
+    }
+    
+    /// Stop searching once `cancel` fires and return the incumbent
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+    
+    /// Report nodes explored and the incumbent's cost to `observer`
+    pub fn with_progress(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.progress = SearchProgress::new(Some(observer), 0);
+        self
+    }
+    
+    /// False when the last `solve` was cancelled before its tour was proven optimal
+    pub fn is_proven(&self) -> bool {
+        self.proven
+    }
+    
+    /// Search nodes explored by the last `solve`
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+    
+    pub fn solve(&mut self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        if self.n == 0 {
+            return Ok((0.0, vec![]));
+        }
+        
+        if self.n == 1 {
+            return Ok((0.0, vec![0]));
+        }
+        
+        // Upper bound awal dari nearest neighbour (+ local search untuk matriks simetrik)
+        let (mut cost, mut path) = ConstructionHeuristic::new(
+            self.distance_matrix.clone(),
+            Construction::NearestNeighbour,
+        ).solve(false)?;
+        if self.bound == Bound::OneTree {
+            cost = LocalSearch::new(self.distance_matrix.clone()).improve(&mut path, false);
+        }
+        self.best_cost = cost;
+        self.best_path = path;
+        self.nodes = 0;
+        self.proven = true;
+        self.progress.restart();
+        self.progress.update(0, self.best_cost);
+        
+        println!("  • Initial upper bound {:.1} ({:?} lower bound)", self.best_cost, self.bound);
+        
+        let mut path = vec![0];
+        let mut visited = vec![false; self.n];
+        visited[0] = true;
+        
+        match self.bound {
+            Bound::ReducedCost => {
+                let mut matrix = self.distance_matrix.clone();
+                for (i, row) in matrix.iter_mut().enumerate() {
+                    row[i] = f64::INFINITY;
+                }
+                let root_bound = reduce(&mut matrix);
+                self.search_reduced(&mut path, &mut visited, matrix, root_bound, verbose);
+            }
+            Bound::OneTree => {
+                // Penalties of the root Held-Karp ascent warm-start every node
+                let (_, penalties) = LowerBound::new(self.distance_matrix.clone()).held_karp(self.best_cost);
+                self.search_one_tree(&mut path, &mut visited, 0.0, &penalties, verbose);
+            }
+        }
+        
+        self.progress.finish(self.nodes as u64, self.best_cost);
+        if self.proven {
+            println!("  • Explored {} nodes", self.nodes);
+        } else {
+            println!("  • Cancelled after {} nodes, keeping the best tour so far", self.nodes);
+        }
+        
+        Ok((self.best_cost, self.best_path.clone()))
+    }
+    
+    /// DFS with reduced cost matrices; `matrix` is already reduced for the current node
+    fn search_reduced(
+        &mut self,
+        path: &mut Vec<usize>,
+        visited: &mut [bool],
+        matrix: Vec<Vec<f64>>,
+        bound: f64,
+        verbose: bool,
+    ) {
+        if !self.proven || self.cancel.is_cancelled() {
+            self.proven = false;
+            return;
+        }
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(REPORT_EVERY) {
+            self.progress.update(self.nodes as u64, self.best_cost);
+        }
+        let current = *path.last().unwrap();
+        
+        if path.len() == self.n {
+            self.record_tour(path, verbose);
+            return;
+        }
+        
+        // Expand children, most promising bound first
+        let mut children = Vec::new();
+        for next in 0..self.n {
+            if visited[next] || matrix[current][next].is_infinite() {
+                continue;
+            }
+            
+            let mut child = matrix.clone();
+            child[current].iter_mut().for_each(|d| *d = f64::INFINITY);
+            child.iter_mut().for_each(|row| row[next] = f64::INFINITY);
+            if path.len() + 1 < self.n {
+                child[next][0] = f64::INFINITY; // No early return to the start
+            }
+            
+            let child_bound = bound + matrix[current][next] + reduce(&mut child);
+            if child_bound < self.best_cost - EPSILON {
+                children.push((child_bound, next, child));
+            }
+        }
+        children.sort_by(|a, b| a.0.total_cmp(&b.0));
+        
+        for (child_bound, next, child) in children {
+            if child_bound >= self.best_cost - EPSILON {
+                break;
+            }
+            
+            path.push(next);
+            visited[next] = true;
+            self.search_reduced(path, visited, child, child_bound, verbose);
+            visited[next] = false;
+            path.pop();
+        }
+    }
+    
+    /// DFS with the spanning tree bound; `cost` is the length of the partial path and
+    /// `penalties` the node penalties that produced this node's bound (warm start for children)
+    fn search_one_tree(
+        &mut self,
+        path: &mut Vec<usize>,
+        visited: &mut [bool],
+        cost: f64,
+        penalties: &[f64],
+        verbose: bool,
+    ) {
+        if !self.proven || self.cancel.is_cancelled() {
+            self.proven = false;
+            return;
+        }
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(REPORT_EVERY) {
+            self.progress.update(self.nodes as u64, self.best_cost);
+        }
+        let current = *path.last().unwrap();
+        
+        if path.len() == self.n {
+            self.record_tour(path, verbose);
+            return;
+        }
+        
+        let mut children = Vec::new();
+        for next in 0..self.n {
+            if visited[next] {
+                continue;
+            }
+            
+            let child_cost = cost + self.distance_matrix[current][next];
+            let mut child_penalties = penalties.to_vec();
+            visited[next] = true;
+            let child_bound = child_cost + self.remaining_tree(visited, next, &mut child_penalties, child_cost);
+            visited[next] = false;
+            
+            if child_bound < self.best_cost - EPSILON {
+                children.push((child_bound, next, child_cost, child_penalties));
+            }
+        }
+        children.sort_by(|a, b| a.0.total_cmp(&b.0));
+        
+        for (child_bound, next, child_cost, child_penalties) in children {
+            if child_bound >= self.best_cost - EPSILON {
+                break;
+            }
+            
+            path.push(next);
+            visited[next] = true;
+            self.search_one_tree(path, visited, child_cost, &child_penalties, verbose);
+            visited[next] = false;
+            path.pop();
+        }
+    }
+    
+    /// Close the tour and keep it if it beats the incumbent
+    fn record_tour(&mut self, path: &[usize], verbose: bool) {
+        let cost = tour_cost(&self.distance_matrix, path);
+        if cost < self.best_cost {
+            self.best_cost = cost;
+            self.best_path = path.to_vec();
+            self.progress.update(self.nodes as u64, cost);
+            
+            if verbose {
+                println!("    • Improved tour {:.1} after {} nodes", cost, self.nodes);
+            }
+        }
+    }
+    
+    /// Lower bound on the remaining Hamiltonian path from `last` back to city 0.
+    /// That path is a spanning tree of the unvisited cities plus both endpoints in which
+    /// unvisited cities have degree 2 and the endpoints degree 1, so a penalised MST minus
+    /// the penalty of those target degrees is a valid bound. A few subgradient steps tighten
+    /// `penalties` in place; `path_cost` is only used to stop once the node can be pruned.
+    fn remaining_tree(&self, visited: &[bool], last: usize, penalties: &mut [f64], path_cost: f64) -> f64 {
+        let mut nodes: Vec<usize> = (0..self.n).filter(|&c| !visited[c]).collect();
+        if nodes.is_empty() {
+            return self.distance_matrix[last][0];
+        }
+        nodes.push(last);
+        nodes.push(0);
+        
+        let target = |k: usize| if k + 2 >= nodes.len() { 1 } else { 2 };
+        let mut best_bound = f64::NEG_INFINITY;
+        let mut best_penalties = penalties.to_vec();
+        let mut step_scale = 1.0;
+        
+        for _ in 0..NODE_ITERATIONS {
+            let (tree_cost, parent) = penalised_tree(&self.distance_matrix, &nodes, penalties);
+            let mut degree = vec![0i32; nodes.len()];
+            for v in 1..nodes.len() {
+                degree[v] += 1;
+                degree[parent[v]] += 1;
+            }
+            
+            let offset: f64 = (0..nodes.len()).map(|k| target(k) as f64 * penalties[nodes[k]]).sum();
+            let bound = tree_cost - offset;
+            if bound > best_bound {
+                best_bound = bound;
+                best_penalties.copy_from_slice(penalties);
+            } else {
+                step_scale /= 2.0;
+            }
+            
+            let norm: i32 = (0..nodes.len()).map(|k| (degree[k] - target(k)).pow(2)).sum();
+            if norm == 0 || path_cost + best_bound >= self.best_cost - EPSILON {
+                break; // Tree is already the path, or the node is pruned anyway
+            }
+            
+            let step = step_scale * (self.best_cost - path_cost - bound).max(EPSILON) / norm as f64;
+            for k in 0..nodes.len() {
+                penalties[nodes[k]] += step * (degree[k] - target(k)) as f64;
+            }
+        }
+        
+        penalties.copy_from_slice(&best_penalties);
+        best_bound
+    }
+}
+
+/// Subtract row and column minima in place, returning the total subtracted
+fn reduce(matrix: &mut [Vec<f64>]) -> f64 {
+    let n = matrix.len();
+    let mut total = 0.0;
+    
+    for row in matrix.iter_mut() {
+        let min = row.iter().cloned().fold(f64::INFINITY, f64::min);
+        if min.is_finite() && min > 0.0 {
+            row.iter_mut().for_each(|d| *d -= min);
+            total += min;
+        }
+    }
+    
+    for col in 0..n {
+        let min = matrix.iter().map(|row| row[col]).fold(f64::INFINITY, f64::min);
+        if min.is_finite() && min > 0.0 {
+            matrix.iter_mut().for_each(|row| row[col] -= min);
+            total += min;
+        }
+    }
+    
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pseudo_random_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_matches_held_karp() {
+        for symmetric in [false, true] {
+            let matrix = pseudo_random_matrix(10, symmetric);
+            let (expected, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+            
+            for bound in [Bound::ReducedCost, Bound::OneTree] {
+                if bound == Bound::OneTree && !symmetric {
+                    continue;
+                }
+                let (cost, path) = BranchAndBound::with_bound(matrix.clone(), bound).solve(false).unwrap();
+                
+                assert!((cost - expected).abs() < 1e-9);
+                assert_eq!(path[0], 0);
+                assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-9);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_two_cities() {
+        let matrix = vec![vec![0.0, 4.0], vec![6.0, 0.0]];
+        let (cost, path) = BranchAndBound::new(matrix).solve(false).unwrap();
+        
+        assert_eq!(cost, 10.0);
+        assert_eq!(path, vec![0, 1]);
+    }
+    
+    #[test]
+    fn test_cancel_keeps_incumbent() {
+        let matrix = pseudo_random_matrix(12, true);
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        
+        let mut search = BranchAndBound::new(matrix.clone()).with_cancel(cancel);
+        let (cost, path) = search.solve(false).unwrap();
+        assert!(!search.is_proven());
+        assert_eq!(path.len(), 12);
+        assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-9);
+        
+        let mut search = BranchAndBound::new(matrix);
+        search.solve(false).unwrap();
+        assert!(search.is_proven());
+    }
+}
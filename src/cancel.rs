@@ -0,0 +1,60 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Stop signal shared by a solver and its caller: raised by `cancel` (from any thread)
+/// or once the deadline passes. Clones share the same signal.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Cancel automatically once `time_limit` has passed from now
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.deadline = time_limit.map(|limit| Instant::now() + limit);
+        self
+    }
+    
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.cancel();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_clones_share_the_signal() {
+        let token = CancelToken::new();
+        let worker = token.clone();
+        assert!(!worker.is_cancelled());
+        
+        std::thread::spawn(move || token.cancel()).join().unwrap();
+        assert!(worker.is_cancelled());
+    }
+    
+    #[test]
+    fn test_deadline() {
+        assert!(CancelToken::new().with_time_limit(Some(Duration::ZERO)).is_cancelled());
+        assert!(!CancelToken::new().with_time_limit(Some(Duration::from_secs(3600))).is_cancelled());
+        assert!(!CancelToken::new().with_time_limit(None).is_cancelled());
+    }
+}
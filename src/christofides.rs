@@ -0,0 +1,236 @@
+use anyhow::Result;
+
+use crate::heuristics::rotate_to_start;
+use crate::matching::min_cost_perfect_matching;
+use crate::tsp_solver::{is_symmetric, tour_cost};
+use crate::TSPError;
+
+/// Christofides' 1.5-approximation for metric instances
+pub struct Christofides {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    /// City names for error messages (empty: cities are numbered)
+    names: Vec<String>,
+}
+
+impl Christofides {
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            names: Vec::new(),
+        }
+    }
+    
+    pub fn with_names(mut self, names: Vec<String>) -> Self {
+        self.names = names;
+        self
+    }
+    
+    /// MST + minimum-cost perfect matching on odd-degree cities, Euler tour, shortcut;
+    /// refuses matrices that are asymmetric or break the triangle inequality
+    pub fn solve(&self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        self.validate()?;
+        
+        if self.n < 3 {
+            let path: Vec<usize> = (0..self.n).collect();
+            return Ok((tour_cost(&self.distance_matrix, &path), path));
+        }
+        
+        let d = &self.distance_matrix;
+        let mut adjacency = self.spanning_tree();
+        let tree_cost: f64 = (0..self.n)
+            .flat_map(|i| adjacency[i].iter().filter(move |&&j| i < j).map(move |&j| d[i][j]))
+            .sum();
+        
+        let odd: Vec<usize> = (0..self.n).filter(|&c| adjacency[c].len() % 2 == 1).collect();
+        let odd_costs: Vec<Vec<f64>> = odd.iter()
+            .map(|&a| odd.iter().map(|&b| d[a][b]).collect())
+            .collect();
+        let mate = min_cost_perfect_matching(&odd_costs);
+        
+        let mut matching_cost = 0.0;
+        for (i, &j) in mate.iter().enumerate() {
+            if i < j {
+                let (a, b) = (odd[i], odd[j]);
+                adjacency[a].push(b);
+                adjacency[b].push(a);
+                matching_cost += d[a][b];
+            }
+        }
+        
+        println!("  • Spanning tree {:.1}, {} odd-degree cities matched for {:.1}",
+                tree_cost, odd.len(), matching_cost);
+        
+        let circuit = euler_circuit(adjacency);
+        
+        // Shortcut: keep the first visit of every city
+        let mut seen = vec![false; self.n];
+        let path: Vec<usize> = circuit.into_iter()
+            .filter(|&c| !std::mem::replace(&mut seen[c], true))
+            .collect();
+        let path = rotate_to_start(path);
+        let cost = tour_cost(d, &path);
+        
+        if verbose {
+            println!("    • Tour {:.1} ≤ 1.5 × optimum; spanning tree {:.1} is a lower bound", cost, tree_cost);
+        }
+        
+        Ok((cost, path))
+    }
+    
+    fn validate(&self) -> Result<()> {
+        let d = &self.distance_matrix;
+        
+        if !is_symmetric(d) {
+            return Err(TSPError::GraphValidation(
+                "Christofides requires a symmetric distance matrix".to_string()
+            ).into());
+        }
+        
+        // Only floating-point noise is forgiven; any real violation voids the 1.5 guarantee
+        let tolerance = 1e-9 * d.iter().flatten().cloned().fold(0.0, f64::max);
+        
+        for (i, direct) in d.iter().enumerate() {
+            for (k, onward) in d.iter().enumerate() {
+                let via = direct[k];
+                // Symmetric, so j > i suffices; branchless so the inner loop vectorizes
+                let broken = direct[i + 1..].iter()
+                    .zip(&onward[i + 1..])
+                    .fold(false, |acc, (&ij, &kj)| acc | (ij > via + kj + tolerance));
+                
+                if broken {
+                    let j = (i + 1..self.n).find(|&j| direct[j] > via + onward[j] + tolerance).unwrap();
+                    return Err(TSPError::GraphValidation(
+                        format!("Christofides requires the triangle inequality, but distance {} -> {} ({}) is longer than going through {} ({})",
+                               self.name(i), self.name(j), direct[j], self.name(k), via + onward[j])
+                    ).into());
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    fn name(&self, city: usize) -> String {
+        self.names.get(city).cloned().unwrap_or_else(|| format!("city {}", city))
+    }
+    
+    /// Prim's MST as an adjacency list
+    fn spanning_tree(&self) -> Vec<Vec<usize>> {
+        let d = &self.distance_matrix;
+        let mut adjacency = vec![Vec::new(); self.n];
+        let mut in_tree = vec![false; self.n];
+        let mut best: Vec<(f64, usize)> = (0..self.n).map(|c| (d[0][c], 0)).collect();
+        in_tree[0] = true;
+        
+        for _ in 1..self.n {
+            let next = (0..self.n)
+                .filter(|&c| !in_tree[c])
+                .min_by(|&a, &b| best[a].0.total_cmp(&best[b].0))
+                .unwrap();
+            let parent = best[next].1;
+            adjacency[parent].push(next);
+            adjacency[next].push(parent);
+            in_tree[next] = true;
+            
+            for c in 0..self.n {
+                if !in_tree[c] && d[next][c] < best[c].0 {
+                    best[c] = (d[next][c], next);
+                }
+            }
+        }
+        
+        adjacency
+    }
+}
+
+/// Hierholzer's algorithm on a connected multigraph with even degrees, from city 0
+fn euler_circuit(mut adjacency: Vec<Vec<usize>>) -> Vec<usize> {
+    let mut stack = vec![0];
+    let mut circuit = Vec::new();
+    
+    while let Some(&city) = stack.last() {
+        match adjacency[city].pop() {
+            Some(next) => {
+                // Hapus edge balik dari kota tujuan
+                let back = adjacency[next].iter().position(|&c| c == city).unwrap();
+                adjacency[next].swap_remove(back);
+                stack.push(next);
+            }
+            None => {
+                circuit.push(city);
+                stack.pop();
+            }
+        }
+    }
+    
+    circuit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{points_matrix, polygons_matrix};
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_within_approximation_ratio() {
+        // From 15 points on, the matching also has to expand blossoms it contracted
+        for n in [5, 8, 12, 15, 16] {
+            let matrix = points_matrix(n);
+            let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+            let (cost, path) = Christofides::new(matrix.clone()).solve(false).unwrap();
+            
+            let mut sorted = path.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+            assert!(cost >= optimum - 1e-9);
+            assert!(cost <= 1.5 * optimum + 1e-9, "n = {}: {} vs optimum {}", n, cost, optimum);
+        }
+    }
+    
+    #[test]
+    fn test_odd_cycles() {
+        // The odd-degree cities lie on odd polygons, which the matching contracts into blossoms
+        for sizes in [&[3, 3, 3, 5][..], &[5, 5, 3]] {
+            let matrix = polygons_matrix(sizes);
+            let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+            let (cost, path) = Christofides::new(matrix.clone()).solve(false).unwrap();
+            
+            assert_eq!(path.len(), matrix.len());
+            assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-9);
+            assert!(cost <= 1.5 * optimum + 1e-9, "{:?}: {} vs optimum {}", sizes, cost, optimum);
+        }
+    }
+    
+    #[test]
+    fn test_refuses_asymmetric_and_non_metric() {
+        let asymmetric = vec![
+            vec![0.0, 5.0, 8.0],
+            vec![6.0, 0.0, 4.0],
+            vec![9.0, 5.0, 0.0],
+        ];
+        let non_metric = vec![
+            vec![0.0, 1.0, 10.0],
+            vec![1.0, 0.0, 1.0],
+            vec![10.0, 1.0, 0.0],
+        ];
+        // Integer distances broken by a single unit are not rounding noise
+        let barely_non_metric = vec![
+            vec![0.0, 1.0, 3.0],
+            vec![1.0, 0.0, 1.0],
+            vec![3.0, 1.0, 0.0],
+        ];
+        
+        for matrix in [asymmetric, non_metric.clone(), barely_non_metric] {
+            let err = Christofides::new(matrix).solve(false).unwrap_err();
+            assert!(matches!(err.downcast_ref::<TSPError>(), Some(TSPError::GraphValidation(_))));
+        }
+        
+        let names = vec!["Jakarta".to_string(), "Bandung".to_string(), "Surabaya".to_string()];
+        let err = Christofides::new(non_metric).with_names(names).solve(false).unwrap_err();
+        assert!(err.to_string().contains("Jakarta -> Surabaya (10) is longer than going through Bandung (2)"), "{}", err);
+    }
+}
@@ -0,0 +1,394 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::cancel::CancelToken;
+use crate::heuristics::{rotate_to_start, Construction, ConstructionHeuristic};
+use crate::progress::{ProgressObserver, SearchProgress};
+use crate::rng::Rng;
+use crate::tsp_solver::{tour_cost, ConvergencePoint};
+
+/// Individuals entering each tournament
+const TOURNAMENT_SIZE: usize = 3;
+
+/// Best individuals copied unchanged into the next generation
+const ELITES: usize = 2;
+
+/// Recombination operator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crossover {
+    /// Order crossover: keep a slice of one parent, fill the rest in the other's order
+    Order,
+    /// Partially mapped crossover: keep a slice, place the other parent's cities through the slice mapping
+    PartiallyMapped,
+    /// Edge recombination: build the child from the union of both parents' edges
+    EdgeRecombination,
+}
+
+impl FromStr for Crossover {
+    type Err = String;
+    
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "ox" => Ok(Crossover::Order),
+            "pmx" => Ok(Crossover::PartiallyMapped),
+            "erx" => Ok(Crossover::EdgeRecombination),
+            _ => Err(format!("unknown crossover '{}'", name)),
+        }
+    }
+}
+
+pub struct GeneticAlgorithm {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    seed: u64,
+    population_size: usize,
+    generations: usize,
+    mutation_rate: f64,
+    crossover: Crossover,
+    time_limit: Option<Duration>,
+    cancel: CancelToken,
+    observer: Option<Arc<dyn ProgressObserver>>,
+    trace: Vec<ConvergencePoint>,
+}
+
+impl GeneticAlgorithm {
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            seed: 42,
+            population_size: 100,
+            generations: 500,
+            mutation_rate: 0.1,
+            crossover: Crossover::Order,
+            time_limit: None,
+            cancel: CancelToken::new(),
+            observer: None,
+            trace: Vec::new(),
+        }
+    }
+    
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    
+    pub fn with_population(mut self, population_size: usize) -> Self {
+        self.population_size = population_size.max(ELITES + 1);
+        self
+    }
+    
+    pub fn with_generations(mut self, generations: usize) -> Self {
+        self.generations = generations;
+        self
+    }
+    
+    pub fn with_mutation_rate(mut self, mutation_rate: f64) -> Self {
+        self.mutation_rate = mutation_rate.clamp(0.0, 1.0);
+        self
+    }
+    
+    pub fn with_crossover(mut self, crossover: Crossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+    
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+    
+    /// Stop evolving once `cancel` fires and return the best tour so far
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+    
+    /// Report generations done and the best cost so far to `observer`
+    pub fn with_progress(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+    
+    /// Best and mean population cost per generation over the last run
+    pub fn trace(&self) -> &[ConvergencePoint] {
+        &self.trace
+    }
+    
+    /// Evolve the population for the configured generations (or time) and return the best tour
+    pub fn solve(&mut self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        self.trace.clear();
+        
+        if self.n < 4 {
+            let path: Vec<usize> = (0..self.n).collect();
+            return Ok((tour_cost(&self.distance_matrix, &path), path));
+        }
+        
+        let mut rng = Rng::new(self.seed);
+        let start = Instant::now();
+        let reporter = SearchProgress::new(self.observer.clone(), self.generations as u64);
+        
+        println!("  • Evolving {} individuals for up to {} generations, {:?} crossover (seed {})",
+                self.population_size, self.generations, self.crossover, self.seed);
+        
+        // Populasi awal: satu tour nearest neighbour, sisanya acak
+        let mut population: Vec<Vec<usize>> = Vec::with_capacity(self.population_size);
+        population.push(
+            ConstructionHeuristic::new(self.distance_matrix.clone(), Construction::NearestNeighbour)
+                .nearest_neighbour_from(0),
+        );
+        while population.len() < self.population_size {
+            let mut tour: Vec<usize> = (0..self.n).collect();
+            rng.shuffle(&mut tour);
+            population.push(tour);
+        }
+        let mut costs: Vec<f64> = population.iter().map(|t| tour_cost(&self.distance_matrix, t)).collect();
+        
+        for generation in 1..=self.generations {
+            if self.time_limit.is_some_and(|limit| start.elapsed() >= limit) || self.cancel.is_cancelled() {
+                break;
+            }
+            
+            // Elitism
+            let mut ranked: Vec<usize> = (0..population.len()).collect();
+            ranked.sort_by(|&a, &b| costs[a].total_cmp(&costs[b]));
+            let mut next: Vec<Vec<usize>> = ranked.iter().take(ELITES).map(|&i| population[i].clone()).collect();
+            
+            while next.len() < self.population_size {
+                let first = &population[self.tournament(&costs, &mut rng)];
+                let second = &population[self.tournament(&costs, &mut rng)];
+                
+                let mut child = match self.crossover {
+                    Crossover::Order => order_crossover(first, second, &mut rng),
+                    Crossover::PartiallyMapped => partially_mapped_crossover(first, second, &mut rng),
+                    Crossover::EdgeRecombination => edge_recombination(first, second, &mut rng),
+                };
+                
+                if rng.next_f64() < self.mutation_rate {
+                    mutate(&mut child, &mut rng);
+                }
+                next.push(child);
+            }
+            
+            population = next;
+            costs = population.iter().map(|t| tour_cost(&self.distance_matrix, t)).collect();
+            
+            let best = costs.iter().cloned().fold(f64::INFINITY, f64::min);
+            let mean = costs.iter().sum::<f64>() / costs.len() as f64;
+            self.trace.push(ConvergencePoint { iteration: generation, current: mean, best });
+            reporter.update(generation as u64, best);
+            
+            if verbose {
+                println!("    • Generation {}: best {:.1}, mean {:.1}", generation, best, mean);
+            }
+        }
+        
+        let best = (0..population.len())
+            .min_by(|&a, &b| costs[a].total_cmp(&costs[b]))
+            .unwrap();
+        let path = rotate_to_start(population.swap_remove(best));
+        let cost = tour_cost(&self.distance_matrix, &path);
+        reporter.finish(self.trace.len() as u64, cost);
+        println!("  • {} generations in {:.2?}", self.trace.len(), start.elapsed());
+        
+        Ok((cost, path))
+    }
+    
+    /// Index of the cheapest of `TOURNAMENT_SIZE` random individuals
+    fn tournament(&self, costs: &[f64], rng: &mut Rng) -> usize {
+        (0..TOURNAMENT_SIZE)
+            .map(|_| rng.below(costs.len()))
+            .min_by(|&a, &b| costs[a].total_cmp(&costs[b]))
+            .unwrap()
+    }
+}
+
+/// Two cut points a <= b
+fn cut_points(n: usize, rng: &mut Rng) -> (usize, usize) {
+    let (a, b) = (rng.below(n), rng.below(n));
+    (a.min(b), a.max(b))
+}
+
+/// OX: child keeps first[a..=b]; the other positions, starting after b, take the
+/// remaining cities in the order they appear in `second` (also starting after b)
+fn order_crossover(first: &[usize], second: &[usize], rng: &mut Rng) -> Vec<usize> {
+    let n = first.len();
+    let (a, b) = cut_points(n, rng);
+    
+    let mut child = vec![usize::MAX; n];
+    let mut used = vec![false; n];
+    for i in a..=b {
+        child[i] = first[i];
+        used[first[i]] = true;
+    }
+    
+    let mut slot = (b + 1) % n;
+    for k in 0..n {
+        let city = second[(b + 1 + k) % n];
+        if !used[city] {
+            child[slot] = city;
+            used[city] = true;
+            slot = (slot + 1) % n;
+        }
+    }
+    
+    child
+}
+
+/// PMX: child keeps first[a..=b]; cities of `second` displaced from the slice follow the
+/// mapping first[i] <-> second[i] until they land outside it; the rest is copied from `second`
+fn partially_mapped_crossover(first: &[usize], second: &[usize], rng: &mut Rng) -> Vec<usize> {
+    let n = first.len();
+    let (a, b) = cut_points(n, rng);
+    
+    let mut position_in_second = vec![0; n];
+    for (i, &city) in second.iter().enumerate() {
+        position_in_second[city] = i;
+    }
+    
+    let mut child = vec![usize::MAX; n];
+    let mut used = vec![false; n];
+    for i in a..=b {
+        child[i] = first[i];
+        used[first[i]] = true;
+    }
+    
+    for (i, &city) in second.iter().enumerate().take(b + 1).skip(a) {
+        if used[city] {
+            continue;
+        }
+        
+        let mut pos = i;
+        while (a..=b).contains(&pos) {
+            pos = position_in_second[first[pos]];
+        }
+        child[pos] = city;
+        used[city] = true;
+    }
+    
+    for i in 0..n {
+        if child[i] == usize::MAX {
+            child[i] = second[i];
+        }
+    }
+    
+    child
+}
+
+/// ERX: walk from first[0], always to the neighbour (in either parent) that has the
+/// fewest unused neighbours left; ties and dead ends are broken at random
+fn edge_recombination(first: &[usize], second: &[usize], rng: &mut Rng) -> Vec<usize> {
+    let n = first.len();
+    
+    // Edge table: at most four distinct neighbours per city
+    let mut edges = vec![[0usize; 4]; n];
+    let mut degree = vec![0usize; n];
+    let mut add = |a: usize, b: usize, edges: &mut Vec<[usize; 4]>| {
+        if !edges[a][..degree[a]].contains(&b) {
+            edges[a][degree[a]] = b;
+            degree[a] += 1;
+        }
+    };
+    for parent in [first, second] {
+        for i in 0..n {
+            let (city, next) = (parent[i], parent[(i + 1) % n]);
+            add(city, next, &mut edges);
+            add(next, city, &mut edges);
+        }
+    }
+    
+    let mut used = vec![false; n];
+    let mut child = Vec::with_capacity(n);
+    let mut current = first[0];
+    
+    loop {
+        child.push(current);
+        used[current] = true;
+        if child.len() == n {
+            break;
+        }
+        
+        let neighbours = edges[current];
+        for &neighbour in &neighbours[..degree[current]] {
+            let k = edges[neighbour][..degree[neighbour]].iter().position(|&c| c == current).unwrap();
+            degree[neighbour] -= 1;
+            edges[neighbour].swap(k, degree[neighbour]);
+        }
+        
+        let options = &neighbours[..degree[current]];
+        current = if options.is_empty() {
+            let unused: Vec<usize> = (0..n).filter(|&c| !used[c]).collect();
+            unused[rng.below(unused.len())]
+        } else {
+            let fewest = options.iter().map(|&c| degree[c]).min().unwrap();
+            let ties: Vec<usize> = options.iter().copied().filter(|&c| degree[c] == fewest).collect();
+            ties[rng.below(ties.len())]
+        };
+    }
+    
+    child
+}
+
+/// Swap two cities or invert a segment, with equal probability
+fn mutate(tour: &mut [usize], rng: &mut Rng) {
+    let (a, b) = cut_points(tour.len(), rng);
+    if rng.below(2) == 0 {
+        tour.swap(a, b);
+    } else {
+        tour[a..=b].reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tsp_solver::TSPSolver;
+    
+    fn is_permutation(tour: &[usize], n: usize) -> bool {
+        let mut sorted = tour.to_vec();
+        sorted.sort();
+        sorted == (0..n).collect::<Vec<_>>()
+    }
+    
+    #[test]
+    fn test_crossovers_produce_permutations() {
+        let mut rng = Rng::new(11);
+        for _ in 0..200 {
+            let mut first: Vec<usize> = (0..15).collect();
+            let mut second = first.clone();
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            
+            assert!(is_permutation(&order_crossover(&first, &second, &mut rng), 15));
+            assert!(is_permutation(&partially_mapped_crossover(&first, &second, &mut rng), 15));
+            assert!(is_permutation(&edge_recombination(&first, &second, &mut rng), 15));
+        }
+    }
+    
+    #[test]
+    fn test_seeded_run_reaches_optimum_on_input1() {
+        let matrix = vec![
+            vec![0.0, 10.0, 15.0, 20.0],
+            vec![10.0, 0.0, 35.0, 25.0],
+            vec![15.0, 35.0, 0.0, 30.0],
+            vec![20.0, 25.0, 30.0, 0.0],
+        ];
+        let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        
+        for crossover in [Crossover::Order, Crossover::PartiallyMapped, Crossover::EdgeRecombination] {
+            let mut ga = GeneticAlgorithm::new(matrix.clone())
+                .with_seed(1)
+                .with_generations(30)
+                .with_crossover(crossover);
+            let (cost, _) = ga.solve(false).unwrap();
+            
+            assert_eq!(cost, optimum);
+            assert_eq!(ga.trace().len(), 30);
+            assert!(ga.trace().iter().all(|p| p.best <= p.current));
+        }
+    }
+}
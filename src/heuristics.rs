@@ -0,0 +1,313 @@
+use anyhow::Result;
+
+use crate::tsp_solver::tour_cost;
+
+/// Construction heuristic used to build a tour from scratch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Construction {
+    /// Nearest neighbour from every start city, best tour kept (O(n³))
+    NearestNeighbour,
+    /// Insert the city whose cheapest insertion is cheapest overall
+    CheapestInsertion,
+    /// Insert the city farthest from the tour at its cheapest position
+    FarthestInsertion,
+    /// Insert the city nearest to the tour at its cheapest position
+    NearestInsertion,
+}
+
+pub struct ConstructionHeuristic {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    method: Construction,
+    /// Start cities tried by nearest neighbour (None = every city)
+    starts: Option<usize>,
+}
+
+impl ConstructionHeuristic {
+    pub fn new(distance_matrix: Vec<Vec<f64>>, method: Construction) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            method,
+            starts: None,
+        }
+    }
+    
+    /// Try nearest neighbour from only `starts` cities, spread evenly over the city
+    /// indices, for O(starts·n²) instead of O(n³)
+    pub fn with_starts(mut self, starts: Option<usize>) -> Self {
+        self.starts = starts;
+        self
+    }
+    
+    /// Build a tour; the returned path always starts at city 0
+    pub fn solve(&self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        if self.n == 0 {
+            return Ok((0.0, vec![]));
+        }
+        
+        if self.n == 1 {
+            return Ok((0.0, vec![0]));
+        }
+        
+        println!("  • Building tour with {:?} for {} cities", self.method, self.n);
+        
+        let path = match self.method {
+            Construction::NearestNeighbour => self.nearest_neighbour(verbose),
+            Construction::CheapestInsertion => self.cheapest_insertion(),
+            Construction::FarthestInsertion => self.selection_insertion(true),
+            Construction::NearestInsertion => self.selection_insertion(false),
+        };
+        
+        Ok((tour_cost(&self.distance_matrix, &path), path))
+    }
+    
+    /// Nearest neighbour tour from every start city (or the chosen sample, city 0
+    /// always), keeping the shortest
+    fn nearest_neighbour(&self, verbose: bool) -> Vec<usize> {
+        let mut best_cost = f64::INFINITY;
+        let mut best_path = Vec::new();
+        
+        let starts = self.starts.map_or(self.n, |starts| starts.clamp(1, self.n));
+        for start in (0..starts).map(|i| i * self.n / starts) {
+            let path = rotate_to_start(self.nearest_neighbour_from(start));
+            let cost = tour_cost(&self.distance_matrix, &path);
+            
+            if cost < best_cost {
+                best_cost = cost;
+                best_path = path;
+                
+                if verbose {
+                    println!("    • Start city {} gives {:.1}", start, cost);
+                }
+            }
+        }
+        
+        best_path
+    }
+    
+    /// Greedy walk to the closest unvisited city
+    pub fn nearest_neighbour_from(&self, start: usize) -> Vec<usize> {
+        let mut unvisited: Vec<usize> = (0..self.n).filter(|&c| c != start).collect();
+        let mut path = Vec::with_capacity(self.n);
+        path.push(start);
+        
+        let mut current = start;
+        while !unvisited.is_empty() {
+            let row = &self.distance_matrix[current];
+            let mut best = 0;
+            for k in 1..unvisited.len() {
+                // Ties go to the lower city index
+                let (a, b) = (unvisited[k], unvisited[best]);
+                if row[a] < row[b] || (row[a] == row[b] && a < b) {
+                    best = k;
+                }
+            }
+            
+            current = unvisited.swap_remove(best);
+            path.push(current);
+        }
+        
+        path
+    }
+    
+    /// Nearest / farthest insertion: pick a city by its distance to the tour,
+    /// then insert it where it adds the least length
+    fn selection_insertion(&self, farthest: bool) -> Vec<usize> {
+        let d = &self.distance_matrix;
+        
+        // Tour as a successor list, starting with city 0 on a self-loop
+        let mut next = vec![usize::MAX; self.n];
+        next[0] = 0;
+        let mut in_tour = vec![false; self.n];
+        in_tour[0] = true;
+        
+        // Jarak setiap kota ke tour (min of both directions)
+        let mut to_tour: Vec<f64> = (0..self.n).map(|c| d[0][c].min(d[c][0])).collect();
+        
+        for _ in 1..self.n {
+            let mut pick = usize::MAX;
+            for city in 0..self.n {
+                if in_tour[city] {
+                    continue;
+                }
+                if pick == usize::MAX
+                    || (farthest && to_tour[city] > to_tour[pick])
+                    || (!farthest && to_tour[city] < to_tour[pick]) {
+                    pick = city;
+                }
+            }
+            
+            let (after, _) = self.cheapest_position(&next, pick);
+            next[pick] = next[after];
+            next[after] = pick;
+            in_tour[pick] = true;
+            
+            for (city, dist) in to_tour.iter_mut().enumerate() {
+                *dist = dist.min(d[pick][city]).min(d[city][pick]);
+            }
+        }
+        
+        successors_to_path(&next)
+    }
+    
+    /// Cheapest insertion: every outside city remembers its best tour edge, and only
+    /// cities whose edge was just split need a full rescan
+    fn cheapest_insertion(&self) -> Vec<usize> {
+        let d = &self.distance_matrix;
+        
+        let mut next = vec![usize::MAX; self.n];
+        next[0] = 0;
+        let mut in_tour = vec![false; self.n];
+        in_tour[0] = true;
+        
+        // (city it goes after, added length) for every city outside the tour
+        let mut best: Vec<(usize, f64)> = (0..self.n).map(|c| (0, d[0][c] + d[c][0])).collect();
+        
+        for _ in 1..self.n {
+            let mut pick = usize::MAX;
+            for city in 0..self.n {
+                if !in_tour[city] && (pick == usize::MAX || best[city].1 < best[pick].1) {
+                    pick = city;
+                }
+            }
+            
+            let after = best[pick].0;
+            let before = next[after];
+            next[pick] = before;
+            next[after] = pick;
+            in_tour[pick] = true;
+            
+            for city in 0..self.n {
+                if in_tour[city] {
+                    continue;
+                }
+                
+                if best[city].0 == after {
+                    // Edge (after, before) no longer exists
+                    best[city] = self.cheapest_position(&next, city);
+                } else {
+                    for (i, j) in [(after, pick), (pick, before)] {
+                        let added = d[i][city] + d[city][j] - d[i][j];
+                        if added < best[city].1 {
+                            best[city] = (i, added);
+                        }
+                    }
+                }
+            }
+        }
+        
+        successors_to_path(&next)
+    }
+    
+    /// Best tour edge (i, next[i]) to insert `city` into; returns (i, added length)
+    fn cheapest_position(&self, next: &[usize], city: usize) -> (usize, f64) {
+        let d = &self.distance_matrix;
+        let mut best = (0, f64::INFINITY);
+        
+        let mut i = 0;
+        loop {
+            let j = next[i];
+            let added = d[i][city] + d[city][j] - d[i][j];
+            if added < best.1 {
+                best = (i, added);
+            }
+            
+            i = j;
+            if i == 0 {
+                break;
+            }
+        }
+        
+        best
+    }
+}
+
+/// Walk a successor list starting from city 0
+fn successors_to_path(next: &[usize]) -> Vec<usize> {
+    let mut path = vec![0];
+    let mut current = next[0];
+    while current != 0 {
+        path.push(current);
+        current = next[current];
+    }
+    path
+}
+
+/// Rotate a closed tour so that it starts at city 0
+pub fn rotate_to_start(mut path: Vec<usize>) -> Vec<usize> {
+    if let Some(zero) = path.iter().position(|&c| c == 0) {
+        path.rotate_left(zero);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pseudo_random_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_tours_are_permutations() {
+        let matrix = pseudo_random_matrix(30, false);
+        
+        for method in [
+            Construction::NearestNeighbour,
+            Construction::CheapestInsertion,
+            Construction::FarthestInsertion,
+            Construction::NearestInsertion,
+        ] {
+            let (cost, path) = ConstructionHeuristic::new(matrix.clone(), method).solve(false).unwrap();
+            
+            let mut sorted = path.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..30).collect::<Vec<_>>());
+            assert_eq!(path[0], 0);
+            assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_not_better_than_optimum() {
+        let matrix = pseudo_random_matrix(9, false);
+        let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        
+        for method in [Construction::NearestNeighbour, Construction::CheapestInsertion] {
+            let (cost, _) = ConstructionHeuristic::new(matrix.clone(), method).solve(false).unwrap();
+            assert!(cost >= optimum - 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_nearest_neighbour_small() {
+        let matrix = vec![
+            vec![0.0, 10.0, 15.0, 20.0],
+            vec![10.0, 0.0, 35.0, 25.0],
+            vec![15.0, 35.0, 0.0, 30.0],
+            vec![20.0, 25.0, 30.0, 0.0],
+        ];
+        
+        let heuristic = ConstructionHeuristic::new(matrix, Construction::NearestNeighbour);
+        assert_eq!(heuristic.nearest_neighbour_from(0), vec![0, 1, 3, 2]);
+        assert_eq!(heuristic.solve(false).unwrap().0, 80.0);
+    }    
+    #[test]
+    fn test_nearest_neighbour_starts() {
+        let matrix = pseudo_random_matrix(40, false);
+        let every = ConstructionHeuristic::new(matrix.clone(), Construction::NearestNeighbour);
+        let (best, _) = every.solve(false).unwrap();
+        
+        let from_zero = tour_cost(&matrix, &every.nearest_neighbour_from(0));
+        let one = every.with_starts(Some(1)).solve(false).unwrap().0;
+        assert_eq!(one, from_zero);
+        
+        let sampled = ConstructionHeuristic::new(matrix, Construction::NearestNeighbour)
+            .with_starts(Some(8))
+            .solve(false)
+            .unwrap()
+            .0;
+        assert!(best <= sampled && sampled <= one);
+    }
+}
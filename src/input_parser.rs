@@ -1,5 +1,11 @@
 use anyhow::{Result, anyhow};
 
+use crate::time_windows::TimeWindow;
+use crate::vehicle_routing::Demands;
+
+/// Keywords that start the optional sections after the matrix
+const SECTION_HEADERS: [&str; 6] = ["TIME_WINDOWS", "DEMANDS", "CAPACITY", "PRECEDENCE", "PRIZES", "CLUSTERS"];
+
 pub struct InputParser;
 
 impl InputParser {
@@ -32,7 +38,7 @@ impl InputParser {
         }
         
         let first_line_parts: Vec<&str> = lines[0].split_whitespace().collect();
-
+        
         first_line_parts.len() > 1 && 
         first_line_parts.iter().any(|part| part.parse::<f64>().is_err())
     }
@@ -51,7 +57,7 @@ impl InputParser {
         if n == 0 {
             return Err(anyhow!("No cities found in first line"));
         }
-
+        
         let mut matrix = Vec::with_capacity(n);
         
         for (i, line) in lines[1..].iter().enumerate() {
@@ -85,7 +91,7 @@ impl InputParser {
         
         Ok((cities, matrix))
     }
-
+    
     fn parse_list_format(&self, lines: &[&str]) -> Result<(Vec<String>, Vec<Vec<f64>>)> {
         let mut matrix_start = 0;
         
@@ -145,6 +151,210 @@ impl InputParser {
         
         Ok((cities, matrix))
     }
+    
+    /// Parse the optional section after the matrix:
+    /// `TIME_WINDOWS` followed by `<city> <ready> <due> <service>` lines.
+    /// Cities without a line have no window and no service time.
+    pub fn parse_time_windows(&self, content: &str, cities: &[String]) -> Result<Option<Vec<TimeWindow>>> {
+        let Some((_, lines)) = self.section(content, "TIME_WINDOWS") else {
+            return Ok(None);
+        };
+        
+        let mut windows = vec![TimeWindow::OPEN; cities.len()];
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 4 {
+                return Err(anyhow!("Time window line '{}' should be: <city> <ready> <due> <service>", line));
+            }
+            
+            let city = cities.iter()
+                .position(|c| c == parts[0])
+                .ok_or_else(|| anyhow!("Unknown city in time windows: {}", parts[0]))?;
+            
+            let numbers: Result<Vec<f64>, _> = parts[1..].iter().map(|s| s.parse::<f64>()).collect();
+            let numbers = numbers.map_err(|_| anyhow!("Invalid number in time window of {}", parts[0]))?;
+            let window = TimeWindow {
+                ready: numbers[0],
+                due: numbers[1],
+                service: numbers[2],
+            };
+            
+            if window.ready > window.due || window.service < 0.0 {
+                return Err(anyhow!("Time window of {} must have ready <= due and service >= 0", parts[0]));
+            }
+            windows[city] = window;
+        }
+        
+        Ok(Some(windows))
+    }
+    
+    /// Parse the optional vehicle-routing sections: `CAPACITY <q>`, and `DEMANDS` followed
+    /// by `<city> <demand>` lines. Cities without a line have no demand.
+    pub fn parse_demands(&self, content: &str, cities: &[String]) -> Result<Option<Demands>> {
+        let capacity = self.section(content, "CAPACITY");
+        let lines = self.section(content, "DEMANDS");
+        
+        let (capacity, lines) = match (capacity, lines) {
+            (None, None) => return Ok(None),
+            (Some((args, _)), Some((_, lines))) => (args, lines),
+            (None, _) => return Err(anyhow!("DEMANDS needs a CAPACITY <q> line")),
+            (_, None) => return Err(anyhow!("CAPACITY needs a DEMANDS section")),
+        };
+        
+        let capacity = match capacity[..] {
+            [q] => q.parse::<f64>().ok().filter(|q| *q > 0.0),
+            _ => None,
+        }.ok_or_else(|| anyhow!("CAPACITY should be a single positive number"))?;
+        
+        let mut demand = vec![0.0; cities.len()];
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 2 {
+                return Err(anyhow!("Demand line '{}' should be: <city> <demand>", line));
+            }
+            
+            let city = cities.iter()
+                .position(|c| c == parts[0])
+                .ok_or_else(|| anyhow!("Unknown city in demands: {}", parts[0]))?;
+            
+            demand[city] = parts[1].parse::<f64>().ok()
+                .filter(|d| *d >= 0.0)
+                .ok_or_else(|| anyhow!("Invalid demand for {}: {}", parts[0], parts[1]))?;
+        }
+        
+        Ok(Some(Demands { demand, capacity }))
+    }
+    
+    /// Parse the optional `PRECEDENCE` section: `<before> <after>` lines, meaning the first
+    /// city has to be visited before the second
+    pub fn parse_precedence(&self, content: &str, cities: &[String]) -> Result<Option<Vec<(usize, usize)>>> {
+        let Some((_, lines)) = self.section(content, "PRECEDENCE") else {
+            return Ok(None);
+        };
+        
+        let city = |name: &str| cities.iter()
+            .position(|c| c == name)
+            .ok_or_else(|| anyhow!("Unknown city in precedence: {}", name));
+        
+        let mut pairs = Vec::with_capacity(lines.len());
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 2 || parts[0] == parts[1] {
+                return Err(anyhow!("Precedence line '{}' should be: <before> <after>", line));
+            }
+            pairs.push((city(parts[0])?, city(parts[1])?));
+        }
+        
+        Ok(Some(pairs))
+    }
+    
+    /// Parse the optional `PRIZES` section: `<city> <prize>` lines. Cities without a line
+    /// have no prize.
+    pub fn parse_prizes(&self, content: &str, cities: &[String]) -> Result<Option<Vec<f64>>> {
+        let Some((_, lines)) = self.section(content, "PRIZES") else {
+            return Ok(None);
+        };
+        
+        let mut prizes = vec![0.0; cities.len()];
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 2 {
+                return Err(anyhow!("Prize line '{}' should be: <city> <prize>", line));
+            }
+            
+            let city = cities.iter()
+                .position(|c| c == parts[0])
+                .ok_or_else(|| anyhow!("Unknown city in prizes: {}", parts[0]))?;
+            
+            prizes[city] = parts[1].parse::<f64>().ok()
+                .filter(|p| *p >= 0.0)
+                .ok_or_else(|| anyhow!("Invalid prize for {}: {}", parts[0], parts[1]))?;
+        }
+        
+        Ok(Some(prizes))
+    }
+    
+    /// Parse the optional `CLUSTERS` section: one line of alternative cities per cluster.
+    /// Returns the cluster id of every city; unlisted cities are clusters of their own.
+    pub fn parse_clusters(&self, content: &str, cities: &[String]) -> Result<Option<Vec<usize>>> {
+        let Some((_, lines)) = self.section(content, "CLUSTERS") else {
+            return Ok(None);
+        };
+        
+        let mut clusters: Vec<Option<usize>> = vec![None; cities.len()];
+        for (id, line) in lines.iter().enumerate() {
+            for name in line.split_whitespace() {
+                let city = cities.iter()
+                    .position(|c| c == name)
+                    .ok_or_else(|| anyhow!("Unknown city in clusters: {}", name))?;
+                
+                if clusters[city].is_some() {
+                    return Err(anyhow!("City {} appears in more than one cluster", name));
+                }
+                clusters[city] = Some(id);
+            }
+        }
+        
+        let mut next_id = lines.len();
+        Ok(Some(clusters.into_iter()
+            .map(|cluster| cluster.unwrap_or_else(|| {
+                next_id += 1;
+                next_id - 1
+            }))
+            .collect()))
+    }
+    
+    /// Parse a tour file: city names in visiting order, separated by whitespace or `->`.
+    /// The start city may be repeated at the end to close the loop.
+    pub fn parse_tour(&self, content: &str, cities: &[String]) -> Result<Vec<usize>> {
+        let mut path = Vec::with_capacity(cities.len());
+        let mut seen = vec![false; cities.len()];
+        
+        let names = content.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .flat_map(|line| line.split_whitespace())
+            .filter(|token| *token != "->");
+        
+        for name in names {
+            let city = cities.iter()
+                .position(|c| c == name)
+                .ok_or_else(|| anyhow!("Unknown city in tour: {}", name))?;
+            
+            if seen[city] {
+                if path.len() == cities.len() && city == path[0] {
+                    continue; // Closing the loop
+                }
+                return Err(anyhow!("City {} appears more than once in tour", name));
+            }
+            
+            seen[city] = true;
+            path.push(city);
+        }
+        
+        if path.len() != cities.len() {
+            return Err(anyhow!(
+                "Tour visits {} cities, expected {}", 
+                path.len(), cities.len()
+            ));
+        }
+        
+        Ok(path)
+    }
+    
+    /// Words after `header` on its own line, and the lines up to the next section header
+    fn section<'a>(&self, content: &'a str, header: &str) -> Option<(Vec<&'a str>, Vec<&'a str>)> {
+        let mut lines = content.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .skip_while(|line| line.split_whitespace().next() != Some(header));
+        
+        let args = lines.next()?.split_whitespace().skip(1).collect();
+        let body = lines
+            .take_while(|line| !SECTION_HEADERS.iter().any(|h| line.split_whitespace().next() == Some(*h)))
+            .collect();
+        Some((args, body))
+    }
 }
 
 #[cfg(test)]
@@ -182,4 +392,59 @@ mod tests {
         assert_eq!(cities.len(), 3);
         assert_eq!(matrix.len(), 3);
     }
+    
+    #[test]
+    fn test_time_windows() {
+        let input = "A B C\n0 10 15\n10 0 20\n15 20 0\nTIME_WINDOWS\nB 5 30 2\n# C has no window";
+        let mut parser = InputParser::new();
+        let (cities, matrix) = parser.parse(input).unwrap();
+        let windows = parser.parse_time_windows(input, &cities).unwrap().unwrap();
+        
+        assert_eq!(matrix.len(), 3);
+        assert_eq!(windows[1], TimeWindow { ready: 5.0, due: 30.0, service: 2.0 });
+        assert_eq!(windows[2], TimeWindow::OPEN);
+        assert!(parser.parse_time_windows("A B\n0 1\n1 0", &cities).unwrap().is_none());
+        assert!(parser.parse_time_windows("TIME_WINDOWS\nB 30 5 0", &cities).is_err());
+    }
+    
+    #[test]
+    fn test_demands() {
+        let input = "A B C\n0 10 15\n10 0 20\n15 20 0\nCAPACITY 8\nDEMANDS\nB 5\nC 3\nTIME_WINDOWS\nB 0 9 1";
+        let mut parser = InputParser::new();
+        let (cities, _) = parser.parse(input).unwrap();
+        let demands = parser.parse_demands(input, &cities).unwrap().unwrap();
+        
+        assert_eq!(demands.capacity, 8.0);
+        assert_eq!(demands.demand, vec![0.0, 5.0, 3.0]);
+        assert_eq!(parser.parse_time_windows(input, &cities).unwrap().unwrap()[1].due, 9.0);
+        assert!(parser.parse_demands("DEMANDS\nB 5", &cities).is_err());
+    }
+    
+    #[test]
+    fn test_optional_sections() {
+        let input = "A B C\n0 10 15\n10 0 20\n15 20 0\nPRECEDENCE\nC B\nA C";
+        let mut parser = InputParser::new();
+        let (cities, _) = parser.parse(input).unwrap();
+        
+        assert_eq!(parser.parse_precedence(input, &cities).unwrap(), Some(vec![(2, 1), (0, 2)]));
+        assert!(parser.parse_precedence("PRECEDENCE\nB B", &cities).is_err());
+        assert!(parser.parse_precedence("PRECEDENCE\nB D", &cities).is_err());
+        
+        let prizes = parser.parse_prizes("PRIZES\nC 4.5\nPRECEDENCE\nA B", &cities).unwrap();
+        assert_eq!(prizes, Some(vec![0.0, 0.0, 4.5]));
+        assert!(parser.parse_prizes("PRIZES\nC -1", &cities).is_err());
+        
+        assert_eq!(parser.parse_clusters("CLUSTERS\nC A", &cities).unwrap(), Some(vec![0, 1, 0]));
+        assert!(parser.parse_clusters("CLUSTERS\nA B\nB C", &cities).is_err());
+    }
+    
+    #[test]
+    fn test_parse_tour() {
+        let cities: Vec<String> = ["A", "B", "C"].iter().map(|s| s.to_string()).collect();
+        let parser = InputParser::new();
+        
+        assert_eq!(parser.parse_tour("A -> C -> B -> A", &cities).unwrap(), vec![0, 2, 1]);
+        assert!(parser.parse_tour("A B", &cities).is_err());
+        assert!(parser.parse_tour("A B D", &cities).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,439 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::cancel::CancelToken;
+use crate::heuristics::{rotate_to_start, Construction, ConstructionHeuristic};
+use crate::local_search::{improvement_percent, nearest_neighbours, LocalSearch, Tour};
+use crate::progress::{ProgressObserver, SearchProgress};
+use crate::tsp_solver::{is_symmetric, tour_cost};
+
+/// Smallest gain accepted as an improvement (absorbs float rounding)
+const EPSILON: f64 = 1e-9;
+
+/// Candidate cities tried for every new edge
+const CANDIDATES: usize = 8;
+
+/// Longest chain of 2-opt steps in a single LK move
+const MAX_DEPTH: usize = 50;
+
+/// Alternatives tried for t3 at the first levels before the chain turns greedy
+const BREADTH: [usize; 3] = [5, 3, 2];
+
+/// Improving moves between two progress reports
+const REPORT_EVERY: usize = 10;
+
+/// How the candidate set of every city is chosen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Candidates {
+    /// Closest cities by distance
+    Nearest,
+    /// Smallest alpha value, i.e. extra cost of forcing the edge into the minimum spanning tree
+    Alpha,
+}
+
+impl FromStr for Candidates {
+    type Err = String;
+    
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "nearest" => Ok(Candidates::Nearest),
+            "alpha" => Ok(Candidates::Alpha),
+            _ => Err(format!("unknown candidate set '{}'", name)),
+        }
+    }
+}
+
+/// Lin-Kernighan style variable-depth search built from chained 2-opt steps (Or-LK).
+/// Assumes a symmetric matrix; asymmetric input is handed to `LocalSearch` instead.
+pub struct LinKernighan {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    candidates: Candidates,
+    cancel: CancelToken,
+    observer: Option<Arc<dyn ProgressObserver>>,
+}
+
+impl LinKernighan {
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            candidates: Candidates::Nearest,
+            cancel: CancelToken::new(),
+            observer: None,
+        }
+    }
+    
+    pub fn with_candidates(mut self, candidates: Candidates) -> Self {
+        self.candidates = candidates;
+        self
+    }
+    
+    /// Stop improving once `cancel` fires and keep the tour reached so far
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+    
+    /// Report improving moves made and the current (best) cost to `observer`
+    pub fn with_progress(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+    
+    /// Farthest insertion tour improved by LK moves
+    pub fn solve(&self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        let (_, mut path) = ConstructionHeuristic::new(
+            self.distance_matrix.clone(),
+            Construction::FarthestInsertion,
+        ).solve(verbose)?;
+        
+        let cost = self.improve(&mut path, verbose);
+        Ok((cost, path))
+    }
+    
+    /// Improve `path` in place until no LK move applies; returns the new cost
+    pub fn improve(&self, path: &mut Vec<usize>, verbose: bool) -> f64 {
+        // Moves only ever shorten the tour, so the current cost is the best one
+        let reporter = SearchProgress::new(self.observer.clone(), 0);
+        if !is_symmetric(&self.distance_matrix) {
+            println!("  • Asymmetric matrix, using orientation-aware local search instead");
+            let after = LocalSearch::new(self.distance_matrix.clone())
+                .with_three_opt(true)
+                .improve(path, verbose);
+            reporter.finish(0, after);
+            return after;
+        }
+        
+        let before = tour_cost(&self.distance_matrix, path);
+        if self.n < 4 {
+            return before;
+        }
+        reporter.update(0, before);
+        
+        let candidates = match self.candidates {
+            Candidates::Nearest => nearest_neighbours(&self.distance_matrix, CANDIDATES),
+            Candidates::Alpha => self.alpha_nearest(CANDIDATES),
+        };
+        
+        let mut tour = Tour::new(path.clone());
+        let mut queue: VecDeque<usize> = path.iter().copied().collect();
+        let mut queued = vec![true; self.n];
+        let mut moves = 0;
+        
+        while let Some(t1) = queue.pop_front() {
+            if self.cancel.is_cancelled() {
+                break;
+            }
+            queued[t1] = false;
+            
+            let touched = self.lk_move(&mut tour, &candidates, t1, true)
+                .or_else(|| self.lk_move(&mut tour, &candidates, t1, false));
+            
+            if let Some(touched) = touched {
+                moves += 1;
+                for c in touched {
+                    if !queued[c] {
+                        queued[c] = true;
+                        queue.push_back(c);
+                    }
+                }
+                
+                if moves % REPORT_EVERY == 0 {
+                    reporter.update(moves as u64, tour_cost(&self.distance_matrix, &tour.order));
+                }
+                if verbose && moves % 100 == 0 {
+                    println!("    • {} moves, cost {:.1}", moves, tour_cost(&self.distance_matrix, &tour.order));
+                }
+            }
+        }
+        
+        *path = rotate_to_start(tour.order);
+        let after = tour_cost(&self.distance_matrix, path);
+        
+        reporter.finish(moves as u64, after);
+        println!("  • Lin-Kernighan: {:.1} -> {:.1} ({:.2}% shorter, {} moves)",
+                before, after, improvement_percent(before, after), moves);
+        
+        after
+    }
+    
+    /// One variable-depth move starting by breaking edge (t1, t2), t2 the successor of t1
+    /// (`forward`) or its predecessor. Each step adds (t2, t3), breaks (t3, t4) and closes
+    /// with (t4, t1) as a 2-opt move. Returns the touched cities if the tour got shorter.
+    fn lk_move(&self, tour: &mut Tour, candidates: &[Vec<usize>], t1: usize, forward: bool) -> Option<Vec<usize>> {
+        let t2 = if forward { tour.succ(t1) } else { tour.pred(t1) };
+        let mut chain = Chain {
+            t1,
+            added: Vec::new(),
+            reversals: Vec::new(),
+            touched: vec![t1, t2],
+        };
+        
+        let gain = self.distance_matrix[t1][t2];
+        if self.deepen(tour, candidates, &mut chain, t2, gain, forward, 0) {
+            Some(chain.touched)
+        } else {
+            None
+        }
+    }
+    
+    /// Steps of the chain with `t3` choices still open: tries up to `BREADTH[level]`
+    /// alternatives, backtracking until a closed tour is shorter. On success the tour
+    /// is left improved (and greedily extended) and true is returned.
+    #[allow(clippy::too_many_arguments)]
+    fn deepen(
+        &self,
+        tour: &mut Tour,
+        candidates: &[Vec<usize>],
+        chain: &mut Chain,
+        t2: usize,
+        gain: f64,
+        forward: bool,
+        level: usize,
+    ) -> bool {
+        if level >= MAX_DEPTH {
+            return false;
+        }
+        
+        let d = &self.distance_matrix;
+        let t1 = chain.t1;
+        let breadth = BREADTH.get(level).copied().unwrap_or(1);
+        
+        for (t3, t4) in self.choices(tour, candidates, chain, t2, gain, forward).into_iter().take(breadth) {
+            let new_gain = gain + d[t3][t4] - d[t2][t3];
+            let segment = self.apply_step(tour, chain, t2, t3, t4, forward);
+            let new_forward = tour.succ(t1) == t4;
+            
+            let closed = new_gain - d[t4][t1];
+            if closed > EPSILON {
+                self.extend(tour, candidates, chain, t4, new_gain, new_forward, closed);
+                return true;
+            }
+            
+            if self.deepen(tour, candidates, chain, t4, new_gain, new_forward, level + 1) {
+                return true;
+            }
+            
+            // Backtrack
+            chain.reversals.pop();
+            chain.added.pop();
+            tour.reverse(segment.1, segment.0);
+        }
+        
+        false
+    }
+    
+    /// Greedy continuation of an already improving chain; keeps the best closed prefix
+    #[allow(clippy::too_many_arguments)]
+    fn extend(
+        &self,
+        tour: &mut Tour,
+        candidates: &[Vec<usize>],
+        chain: &mut Chain,
+        mut t2: usize,
+        mut gain: f64,
+        mut forward: bool,
+        closed: f64,
+    ) {
+        let d = &self.distance_matrix;
+        let t1 = chain.t1;
+        let mut best_gain = closed;
+        let committed = chain.reversals.len();
+        let mut best_depth = committed;
+        
+        while chain.reversals.len() < MAX_DEPTH {
+            let Some(&(t3, t4)) = self.choices(tour, candidates, chain, t2, gain, forward).first() else {
+                break;
+            };
+            
+            gain += d[t3][t4] - d[t2][t3];
+            self.apply_step(tour, chain, t2, t3, t4, forward);
+            forward = tour.succ(t1) == t4;
+            t2 = t4;
+            
+            let closed = gain - d[t4][t1];
+            if closed > best_gain + EPSILON {
+                best_gain = closed;
+                best_depth = chain.reversals.len();
+            }
+        }
+        
+        // Roll back the steps after the best prefix
+        while chain.reversals.len() > best_depth {
+            let (first, last) = chain.reversals.pop().unwrap();
+            chain.added.pop();
+            tour.reverse(last, first);
+        }
+    }
+    
+    /// Feasible (t3, t4) pairs with positive partial gain, best one-edge lookahead first
+    fn choices(&self, tour: &Tour, candidates: &[Vec<usize>], chain: &Chain, t2: usize, gain: f64, forward: bool) -> Vec<(usize, usize)> {
+        let d = &self.distance_matrix;
+        let step = |c: usize, forward: bool| if forward { tour.succ(c) } else { tour.pred(c) };
+        
+        let mut options: Vec<(f64, usize, usize)> = Vec::new();
+        for &t3 in &candidates[t2] {
+            let partial = gain - d[t2][t3];
+            if partial <= EPSILON || t3 == chain.t1 || t3 == step(t2, forward) {
+                continue;
+            }
+            
+            let t4 = step(t3, !forward);
+            if chain.added.contains(&edge(t3, t4)) {
+                continue; // Never break an edge added by this move
+            }
+            
+            options.push((partial + d[t3][t4], t3, t4));
+        }
+        
+        options.sort_by(|a, b| b.0.total_cmp(&a.0));
+        options.into_iter().map(|(_, t3, t4)| (t3, t4)).collect()
+    }
+    
+    /// Add (t2, t3), break (t3, t4) and close with (t4, t1) by one reversal
+    fn apply_step(&self, tour: &mut Tour, chain: &mut Chain, t2: usize, t3: usize, t4: usize, forward: bool) -> (usize, usize) {
+        let (first, last) = if forward { (t2, t4) } else { (t4, t2) };
+        let segment = self.reverse(tour, first, last);
+        
+        chain.added.push(edge(t2, t3));
+        chain.reversals.push(segment);
+        chain.touched.extend([t3, t4]);
+        segment
+    }
+    
+    /// Reverse the shorter of `first`..`last` and its complement (same tour on symmetric
+    /// matrices); returns the segment actually reversed
+    fn reverse(&self, tour: &mut Tour, first: usize, last: usize) -> (usize, usize) {
+        let len = tour.distance(first, last) + 1;
+        let segment = if 2 * len > self.n {
+            (tour.succ(last), tour.pred(first))
+        } else {
+            (first, last)
+        };
+        tour.reverse(segment.0, segment.1);
+        segment
+    }
+    
+    /// Alpha-nearness on the minimum spanning tree: alpha(i, j) = d(i, j) minus the
+    /// longest edge on the tree path between i and j (O(n²) per city list build)
+    fn alpha_nearest(&self, k: usize) -> Vec<Vec<usize>> {
+        let d = &self.distance_matrix;
+        let n = self.n;
+        let k = k.min(n - 1);
+        
+        // Prim's MST
+        let mut in_tree = vec![false; n];
+        let mut key = vec![f64::INFINITY; n];
+        let mut parent = vec![usize::MAX; n];
+        let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); n];
+        key[0] = 0.0;
+        
+        for _ in 0..n {
+            let u = (0..n)
+                .filter(|&v| !in_tree[v])
+                .min_by(|&a, &b| key[a].total_cmp(&key[b]))
+                .unwrap();
+            in_tree[u] = true;
+            if parent[u] != usize::MAX {
+                adjacency[u].push(parent[u]);
+                adjacency[parent[u]].push(u);
+            }
+            
+            for v in 0..n {
+                if !in_tree[v] && d[u][v] < key[v] {
+                    key[v] = d[u][v];
+                    parent[v] = u;
+                }
+            }
+        }
+        
+        // Longest tree edge from i to every city, by walking the tree
+        let mut beta = vec![0.0; n];
+        let mut stack = Vec::with_capacity(n);
+        let mut visited = vec![false; n];
+        
+        (0..n)
+            .map(|i| {
+                visited.iter_mut().for_each(|v| *v = false);
+                beta[i] = 0.0;
+                visited[i] = true;
+                stack.push(i);
+                
+                while let Some(u) = stack.pop() {
+                    for &v in &adjacency[u] {
+                        if !visited[v] {
+                            visited[v] = true;
+                            beta[v] = f64::max(beta[u], d[u][v]);
+                            stack.push(v);
+                        }
+                    }
+                }
+                
+                let mut others: Vec<usize> = (0..n).filter(|&j| j != i).collect();
+                let alpha = |j: usize| d[i][j] - beta[j];
+                others.sort_by(|&a, &b| alpha(a).total_cmp(&alpha(b))
+                    .then(d[i][a].total_cmp(&d[i][b]))
+                    .then(a.cmp(&b)));
+                others.truncate(k);
+                others
+            })
+            .collect()
+    }
+}
+
+/// State of the LK move being built
+struct Chain {
+    t1: usize,
+    added: Vec<(usize, usize)>,
+    reversals: Vec<(usize, usize)>,
+    touched: Vec<usize>,
+}
+
+/// Undirected edge key
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::points_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_matches_optimum_on_small_instance() {
+        let matrix = points_matrix(12);
+        let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        
+        for candidates in [Candidates::Nearest, Candidates::Alpha] {
+            let (cost, path) = LinKernighan::new(matrix.clone())
+                .with_candidates(candidates)
+                .solve(false)
+                .unwrap();
+            
+            assert!(cost >= optimum - 1e-9);
+            assert!(cost <= optimum * 1.05);
+            assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_improves_bad_tour() {
+        let matrix = points_matrix(200);
+        let mut path: Vec<usize> = (0..200).collect();
+        let before = tour_cost(&matrix, &path);
+        
+        let cost = LinKernighan::new(matrix.clone()).improve(&mut path, false);
+        
+        let mut sorted = path.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..200).collect::<Vec<_>>());
+        assert!(cost < before * 0.5);
+        assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-6);
+    }
+}
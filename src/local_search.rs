@@ -0,0 +1,420 @@
+use std::collections::VecDeque;
+
+use crate::heuristics::rotate_to_start;
+use crate::tsp_solver::{is_symmetric, tour_cost};
+
+/// Smallest gain accepted as an improvement (absorbs float rounding)
+const EPSILON: f64 = 1e-9;
+
+/// Candidate neighbours kept per city
+const NEIGHBOURS: usize = 10;
+
+/// Longest segment moved by Or-opt
+const MAX_SEGMENT: usize = 3;
+
+/// Tour stored as city order plus the position of every city
+pub struct Tour {
+    pub order: Vec<usize>,
+    pos: Vec<usize>,
+}
+
+impl Tour {
+    pub fn new(order: Vec<usize>) -> Self {
+        let mut pos = vec![0; order.len()];
+        for (i, &city) in order.iter().enumerate() {
+            pos[city] = i;
+        }
+        Self { order, pos }
+    }
+    
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+    
+    pub fn succ(&self, city: usize) -> usize {
+        self.order[(self.pos[city] + 1) % self.len()]
+    }
+    
+    pub fn pred(&self, city: usize) -> usize {
+        self.order[(self.pos[city] + self.len() - 1) % self.len()]
+    }
+    
+    /// Steps needed to walk forward from `from` to `to`
+    pub fn distance(&self, from: usize, to: usize) -> usize {
+        (self.pos[to] + self.len() - self.pos[from]) % self.len()
+    }
+    
+    /// City `steps` positions after `city`
+    pub fn at(&self, city: usize, steps: usize) -> usize {
+        self.order[(self.pos[city] + steps) % self.len()]
+    }
+    
+    /// Reverse the path from `first` forward to `last` in place
+    pub fn reverse(&mut self, first: usize, last: usize) {
+        let n = self.len();
+        let (mut i, mut j) = (self.pos[first], self.pos[last]);
+        let len = (j + n - i) % n + 1;
+        
+        for _ in 0..len / 2 {
+            self.order.swap(i, j);
+            self.pos[self.order[i]] = i;
+            self.pos[self.order[j]] = j;
+            i = (i + 1) % n;
+            j = (j + n - 1) % n;
+        }
+    }
+    
+    /// Replace the whole order (used by moves that shift many positions)
+    pub fn rebuild(&mut self, order: Vec<usize>) {
+        *self = Tour::new(order);
+    }
+}
+
+/// 2-opt / Or-opt / optional 3-opt improvement with neighbour lists and don't-look bits
+pub struct LocalSearch {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    symmetric: bool,
+    neighbours: Vec<Vec<usize>>,
+    three_opt: bool,
+}
+
+impl LocalSearch {
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let n = distance_matrix.len();
+        let symmetric = is_symmetric(&distance_matrix);
+        let neighbours = nearest_neighbours(&distance_matrix, NEIGHBOURS);
+        
+        Self {
+            distance_matrix,
+            n,
+            symmetric,
+            neighbours,
+            three_opt: false,
+        }
+    }
+    
+    /// Also try segment-exchange 3-opt moves
+    pub fn with_three_opt(mut self, enabled: bool) -> Self {
+        self.three_opt = enabled;
+        self
+    }
+    
+    /// Improve `path` in place until no move applies; returns the new cost
+    pub fn improve(&self, path: &mut Vec<usize>, verbose: bool) -> f64 {
+        let before = tour_cost(&self.distance_matrix, path);
+        if self.n < 4 {
+            return before;
+        }
+        
+        let moves = self.search(path, verbose);
+        let after = tour_cost(&self.distance_matrix, path);
+        
+        println!("  • Local search: {:.1} -> {:.1} ({:.2}% shorter, {} moves)",
+                before, after, improvement_percent(before, after), moves);
+        
+        after
+    }
+    
+    /// Same as `improve` without any output, for solvers that call it many times
+    pub fn improve_quietly(&self, path: &mut Vec<usize>) -> f64 {
+        if self.n >= 4 {
+            self.search(path, false);
+        }
+        tour_cost(&self.distance_matrix, path)
+    }
+    
+    /// Apply improving moves until none is left; returns the number of moves
+    fn search(&self, path: &mut Vec<usize>, verbose: bool) -> usize {
+        let mut tour = Tour::new(path.clone());
+        
+        // Don't-look bits: only cities in the queue are examined
+        let mut queue: VecDeque<usize> = path.iter().copied().collect();
+        let mut queued = vec![true; self.n];
+        let mut moves = 0;
+        
+        while let Some(city) = queue.pop_front() {
+            queued[city] = false;
+            
+            let touched = self.try_two_opt(&mut tour, city)
+                .or_else(|| self.try_or_opt(&mut tour, city))
+                .or_else(|| if self.three_opt { self.try_three_opt(&mut tour, city) } else { None });
+            
+            if let Some(touched) = touched {
+                moves += 1;
+                for c in touched.into_iter().chain(std::iter::once(city)) {
+                    if !queued[c] {
+                        queued[c] = true;
+                        queue.push_back(c);
+                    }
+                }
+                
+                if verbose && moves % 100 == 0 {
+                    println!("    • {} moves, cost {:.1}", moves, tour_cost(&self.distance_matrix, &tour.order));
+                }
+            }
+        }
+        
+        *path = rotate_to_start(tour.order);
+        moves
+    }
+    
+    /// Reverse `first`..`last`; on symmetric matrices the complement gives the same
+    /// tour (mirrored), so the shorter of the two is reversed
+    fn reverse(&self, tour: &mut Tour, first: usize, last: usize) {
+        let len = tour.distance(first, last) + 1;
+        if self.symmetric && 2 * len > self.n {
+            let (after, before) = (tour.succ(last), tour.pred(first));
+            tour.reverse(after, before);
+        } else {
+            tour.reverse(first, last);
+        }
+    }
+    
+    /// Extra length of walking `first`..`last` backwards instead of forwards
+    fn reversal_delta(&self, tour: &Tour, first: usize, last: usize) -> f64 {
+        if self.symmetric {
+            return 0.0;
+        }
+        
+        let d = &self.distance_matrix;
+        let mut delta = 0.0;
+        let mut city = first;
+        while city != last {
+            let next = tour.succ(city);
+            delta += d[next][city] - d[city][next];
+            city = next;
+        }
+        delta
+    }
+    
+    /// 2-opt with `a` as the first endpoint, in both tour directions
+    fn try_two_opt(&self, tour: &mut Tour, a: usize) -> Option<Vec<usize>> {
+        let d = &self.distance_matrix;
+        
+        // Forward: a -> b ... c -> e  becomes  a -> c ... b -> e
+        let b = tour.succ(a);
+        for &c in &self.neighbours[a] {
+            if d[a][b] - d[a][c] <= EPSILON {
+                if self.symmetric { break; } else { continue; }
+            }
+            let e = tour.succ(c);
+            if c == b || e == a {
+                continue;
+            }
+            
+            let delta = d[a][c] + d[b][e] - d[a][b] - d[c][e] + self.reversal_delta(tour, b, c);
+            if delta < -EPSILON {
+                self.reverse(tour, b, c);
+                return Some(vec![a, b, c, e]);
+            }
+        }
+        
+        // Backward: e -> c ... b -> a  becomes  e -> b ... c -> a
+        let b = tour.pred(a);
+        for &c in &self.neighbours[a] {
+            if d[b][a] - d[c][a] <= EPSILON {
+                if self.symmetric { break; } else { continue; }
+            }
+            let e = tour.pred(c);
+            if c == b || e == a {
+                continue;
+            }
+            
+            let delta = d[e][b] + d[c][a] - d[e][c] - d[b][a] + self.reversal_delta(tour, c, b);
+            if delta < -EPSILON {
+                self.reverse(tour, c, b);
+                return Some(vec![a, b, c, e]);
+            }
+        }
+        
+        None
+    }
+    
+    /// Or-opt: move the segment of 1..=3 cities starting at `first` between two
+    /// cities next to one of its endpoints, in either orientation
+    fn try_or_opt(&self, tour: &mut Tour, first: usize) -> Option<Vec<usize>> {
+        let d = &self.distance_matrix;
+        
+        for len in 1..=MAX_SEGMENT.min(self.n - 3) {
+            let last = tour.at(first, len - 1);
+            let prev = tour.pred(first);
+            let next = tour.succ(last);
+            
+            let removed = d[prev][first] + d[last][next] - d[prev][next];
+            if removed <= EPSILON {
+                continue;
+            }
+            let reversed = self.reversal_delta(tour, first, last);
+            let in_segment = |c: usize| tour.distance(first, c) < len;
+            
+            for end in [first, last] {
+                for &c in &self.neighbours[end] {
+                    if self.symmetric && d[end][c] >= removed {
+                        break;
+                    }
+                    if in_segment(c) {
+                        continue;
+                    }
+                    
+                    // Tour edges around c once the segment is taken out
+                    let after = if c == prev { next } else { tour.succ(c) };
+                    let before = if c == next { prev } else { tour.pred(c) };
+                    
+                    for (x, y) in [(c, after), (before, c)] {
+                        if (x, y) == (prev, next) {
+                            continue;
+                        }
+                        
+                        // Orientation that puts `end` next to c
+                        let forward = (end == first) == (x == c);
+                        let (head, tail) = if forward { (first, last) } else { (last, first) };
+                        let added = d[x][head] + d[tail][y] - d[x][y]
+                            + if forward { 0.0 } else { reversed };
+                        
+                        if added - removed < -EPSILON {
+                            self.move_segment(tour, first, len, x, forward);
+                            return Some(vec![prev, next, first, last, x, y]);
+                        }
+                    }
+                }
+            }
+        }
+        
+        None
+    }
+    
+    /// Reinsert the `len` cities starting at `first` right after `x`
+    fn move_segment(&self, tour: &mut Tour, first: usize, len: usize, x: usize, forward: bool) {
+        let mut segment: Vec<usize> = (0..len).map(|k| tour.at(first, k)).collect();
+        if !forward {
+            segment.reverse();
+        }
+        
+        let mut order = Vec::with_capacity(self.n);
+        for k in len..self.n {
+            let city = tour.at(first, k);
+            order.push(city);
+            if city == x {
+                order.extend_from_slice(&segment);
+            }
+        }
+        
+        tour.rebuild(order);
+    }
+    
+    /// Segment exchange 3-opt: a -> b..c -> d..e -> f  becomes  a -> d..e -> b..c -> f
+    /// (orientation preserving, so also valid for asymmetric matrices)
+    fn try_three_opt(&self, tour: &mut Tour, a: usize) -> Option<Vec<usize>> {
+        let d = &self.distance_matrix;
+        let n = self.n;
+        let b = tour.succ(a);
+        
+        for &dd in &self.neighbours[a] {
+            let g1 = d[a][b] - d[a][dd];
+            if g1 <= EPSILON {
+                if self.symmetric { break; } else { continue; }
+            }
+            let rd = tour.distance(a, dd);
+            if rd < 2 {
+                continue;
+            }
+            let c = tour.pred(dd);
+            
+            for &e in &self.neighbours[b] {
+                let g2 = g1 + d[c][dd] - d[e][b];
+                if g2 <= EPSILON {
+                    if self.symmetric { break; } else { continue; }
+                }
+                if tour.distance(a, e) < rd {
+                    continue; // e must lie on d..end
+                }
+                let f = tour.succ(e);
+                
+                let delta = d[a][dd] + d[e][b] + d[c][f] - d[a][b] - d[c][dd] - d[e][f];
+                if delta < -EPSILON {
+                    let re = tour.distance(a, e);
+                    let mut order = Vec::with_capacity(n);
+                    order.push(a);
+                    order.extend((rd..=re).map(|k| tour.at(a, k)));
+                    order.extend((1..rd).map(|k| tour.at(a, k)));
+                    order.extend((re + 1..n).map(|k| tour.at(a, k)));
+                    tour.rebuild(order);
+                    return Some(vec![a, b, c, dd, e, f]);
+                }
+            }
+        }
+        
+        None
+    }
+}
+
+/// The `k` closest cities of every city, closest first (ties by index)
+pub fn nearest_neighbours(matrix: &[Vec<f64>], k: usize) -> Vec<Vec<usize>> {
+    let n = matrix.len();
+    let k = k.min(n.saturating_sub(1));
+    
+    (0..n)
+        .map(|i| {
+            let mut others: Vec<usize> = (0..n).filter(|&j| j != i).collect();
+            let cmp = |a: &usize, b: &usize| matrix[i][*a].total_cmp(&matrix[i][*b]).then(a.cmp(b));
+            if k > 0 && k < others.len() {
+                others.select_nth_unstable_by(k - 1, cmp);
+                others.truncate(k);
+            }
+            others.sort_by(cmp);
+            others
+        })
+        .collect()
+}
+
+/// Percentage by which `after` is shorter than `before`
+pub fn improvement_percent(before: f64, after: f64) -> f64 {
+    if before > 0.0 {
+        100.0 * (before - after) / before
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pseudo_random_matrix;
+    use crate::heuristics::{Construction, ConstructionHeuristic};
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_never_worse_and_valid() {
+        for symmetric in [true, false] {
+            let matrix = pseudo_random_matrix(40, symmetric);
+            let (start_cost, mut path) = ConstructionHeuristic::new(matrix.clone(), Construction::NearestInsertion)
+                .solve(false)
+                .unwrap();
+            
+            let cost = LocalSearch::new(matrix.clone())
+                .with_three_opt(true)
+                .improve(&mut path, false);
+            
+            let mut sorted = path.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..40).collect::<Vec<_>>());
+            assert_eq!(path[0], 0);
+            assert!(cost <= start_cost + 1e-9);
+            assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-6);
+        }
+    }
+    
+    #[test]
+    fn test_reaches_optimum_on_small_instance() {
+        let matrix = pseudo_random_matrix(9, true);
+        let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        
+        // Deliberately bad start: identity order
+        let mut path: Vec<usize> = (0..9).collect();
+        let cost = LocalSearch::new(matrix).with_three_opt(true).improve(&mut path, false);
+        
+        assert!(cost >= optimum - 1e-9);
+        assert!(cost <= optimum * 1.1);
+    }
+}
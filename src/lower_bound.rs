@@ -0,0 +1,289 @@
+use std::time::{Duration, Instant};
+
+use crate::tsp_solver::{is_symmetric, tour_cost};
+
+/// Improvements smaller than this do not count as progress in the subgradient ascent
+const EPSILON: f64 = 1e-9;
+
+/// Iteration cap for the Held-Karp subgradient ascent
+const SUBGRADIENT_ITERATIONS: usize = 1000;
+
+/// Symmetric matrices up to this size still get the O(n³) assignment bound; beyond it the
+/// 1-tree is nearly always stronger and far cheaper
+const SYMMETRIC_ASSIGNMENT_CITIES: usize = 100;
+
+/// Lower bounds on the optimal tour length, used to certify heuristic results.
+/// Tree bounds run on the undirected matrix min(d[i][j], d[j][i]), which keeps
+/// them valid for asymmetric input.
+pub struct LowerBound {
+    distance_matrix: Vec<Vec<f64>>,
+    undirected: Vec<Vec<f64>>,
+    n: usize,
+    /// Time budget for the subgradient ascent
+    time_limit: Option<Duration>,
+}
+
+impl LowerBound {
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let n = distance_matrix.len();
+        let undirected = (0..n)
+            .map(|i| (0..n).map(|j| distance_matrix[i][j].min(distance_matrix[j][i])).collect())
+            .collect();
+        Self {
+            distance_matrix,
+            undirected,
+            n,
+            time_limit: None,
+        }
+    }
+    
+    /// Stop the Held-Karp ascent after `time_limit`, keeping the best bound so far
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+    
+    /// Whether the assignment bound is worth its O(n³): asymmetric or small matrices
+    pub fn assignment_pays_off(&self) -> bool {
+        self.n <= SYMMETRIC_ASSIGNMENT_CITIES || !is_symmetric(&self.distance_matrix)
+    }
+    
+    /// Cheapest assignment of a successor to every city (Hungarian method, O(n³));
+    /// strongest on asymmetric matrices
+    pub fn assignment(&self) -> f64 {
+        if self.n < 3 {
+            return self.trivial();
+        }
+        
+        let n = self.n;
+        let cost = |i: usize, j: usize| if i == j { f64::INFINITY } else { self.distance_matrix[i - 1][j - 1] };
+        
+        // Potentials u (rows) and v (columns); p[j] = row assigned to column j (1-based, 0 = none)
+        let mut u = vec![0.0; n + 1];
+        let mut v = vec![0.0; n + 1];
+        let mut p = vec![0usize; n + 1];
+        let mut way = vec![0usize; n + 1];
+        
+        for row in 1..=n {
+            p[0] = row;
+            let mut j0 = 0;
+            let mut min_slack = vec![f64::INFINITY; n + 1];
+            let mut used = vec![false; n + 1];
+            
+            loop {
+                used[j0] = true;
+                let i0 = p[j0];
+                let mut delta = f64::INFINITY;
+                let mut j1 = 0;
+                
+                for j in 1..=n {
+                    if used[j] {
+                        continue;
+                    }
+                    let slack = cost(i0, j) - u[i0] - v[j];
+                    if slack < min_slack[j] {
+                        min_slack[j] = slack;
+                        way[j] = j0;
+                    }
+                    if min_slack[j] < delta {
+                        delta = min_slack[j];
+                        j1 = j;
+                    }
+                }
+                
+                for j in 0..=n {
+                    if used[j] {
+                        u[p[j]] += delta;
+                        v[j] -= delta;
+                    } else {
+                        min_slack[j] -= delta;
+                    }
+                }
+                
+                j0 = j1;
+                if p[j0] == 0 {
+                    break;
+                }
+            }
+            
+            // Augment along the alternating path
+            while j0 != 0 {
+                let j1 = way[j0];
+                p[j0] = p[j1];
+                j0 = j1;
+            }
+        }
+        
+        (1..=n).map(|j| cost(p[j], j)).sum()
+    }
+    
+    /// Minimum 1-tree: spanning tree over cities 1..n plus the two cheapest edges at city 0
+    pub fn one_tree(&self) -> f64 {
+        if self.n < 3 {
+            return self.trivial();
+        }
+        self.penalised_one_tree(&vec![0.0; self.n]).0
+    }
+    
+    /// Held-Karp (Lagrangian) bound: subgradient ascent on node penalties of the 1-tree,
+    /// with step sizes scaled by `upper_bound`; returns the bound and the best penalties.
+    /// The first iteration always runs, so the bound is never weaker than the 1-tree.
+    pub fn held_karp(&self, upper_bound: f64) -> (f64, Vec<f64>) {
+        let mut penalties = vec![0.0; self.n];
+        if self.n < 3 {
+            return (self.trivial(), penalties);
+        }
+        
+        let mut best_bound = f64::NEG_INFINITY;
+        let mut best_penalties = penalties.clone();
+        let mut step_scale = 2.0;
+        let mut stale = 0;
+        let started = Instant::now();
+        
+        for _ in 0..SUBGRADIENT_ITERATIONS {
+            let (bound, degree) = self.penalised_one_tree(&penalties);
+            if bound > best_bound + EPSILON {
+                best_bound = bound;
+                best_penalties = penalties.clone();
+                stale = 0;
+            } else {
+                stale += 1;
+                if stale >= 10 {
+                    step_scale /= 2.0;
+                    stale = 0;
+                }
+            }
+            
+            let norm: i32 = degree.iter().map(|&d| (d - 2) * (d - 2)).sum();
+            if norm == 0 || step_scale < 1e-6 {
+                break; // 1-tree is a tour, or the steps have vanished
+            }
+            if self.time_limit.is_some_and(|limit| started.elapsed() >= limit) {
+                break; // Out of time; the best bound so far stands
+            }
+            
+            let step = step_scale * (upper_bound - bound).max(EPSILON) / norm as f64;
+            for (pi, &d) in penalties.iter_mut().zip(&degree) {
+                *pi += step * (d - 2) as f64;
+            }
+        }
+        
+        (best_bound, best_penalties)
+    }
+    
+    /// 1-tree with costs d[i][j] + π[i] + π[j], minus 2·Σπ; returns (bound, degree per city)
+    fn penalised_one_tree(&self, penalties: &[f64]) -> (f64, Vec<i32>) {
+        let others: Vec<usize> = (1..self.n).collect();
+        let (tree_cost, parent) = penalised_tree(&self.undirected, &others, penalties);
+        
+        let mut degree = vec![0i32; self.n];
+        for v in 1..others.len() {
+            degree[others[v]] += 1;
+            degree[others[parent[v]]] += 1;
+        }
+        
+        let mut edges: Vec<(f64, usize)> = others.iter()
+            .map(|&c| (self.undirected[0][c] + penalties[0] + penalties[c], c))
+            .collect();
+        edges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut one_tree = tree_cost;
+        for &(d, c) in edges.iter().take(2) {
+            one_tree += d;
+            degree[c] += 1;
+            degree[0] += 1;
+        }
+        
+        (one_tree - 2.0 * penalties.iter().sum::<f64>(), degree)
+    }
+    
+    /// With fewer than three cities the only tour is the optimum
+    fn trivial(&self) -> f64 {
+        tour_cost(&self.distance_matrix, &(0..self.n).collect::<Vec<_>>())
+    }
+}
+
+/// Prim's MST over `nodes` with costs d[i][j] + π[i] + π[j]; returns (cost, parent per node)
+pub fn penalised_tree(matrix: &[Vec<f64>], nodes: &[usize], penalties: &[f64]) -> (f64, Vec<usize>) {
+    let mut in_tree = vec![false; nodes.len()];
+    let mut key = vec![f64::INFINITY; nodes.len()];
+    let mut parent = vec![0; nodes.len()];
+    key[0] = 0.0;
+    let mut total = 0.0;
+    
+    for _ in 0..nodes.len() {
+        let mut u = usize::MAX;
+        for v in 0..nodes.len() {
+            if !in_tree[v] && (u == usize::MAX || key[v] < key[u]) {
+                u = v;
+            }
+        }
+        
+        in_tree[u] = true;
+        total += key[u];
+        
+        for v in 0..nodes.len() {
+            let d = matrix[nodes[u]][nodes[v]] + penalties[nodes[u]] + penalties[nodes[v]];
+            if !in_tree[v] && d < key[v] {
+                key[v] = d;
+                parent[v] = u;
+            }
+        }
+    }
+    
+    (total, parent)
+}
+
+/// Percentage by which `cost` exceeds the lower bound
+pub fn gap_percent(cost: f64, lower_bound: f64) -> f64 {
+    if lower_bound > 0.0 {
+        // Rounding can put a proven-optimal cost a hair below the bound
+        (100.0 * (cost - lower_bound) / lower_bound).max(0.0)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pseudo_random_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_bounds_never_exceed_optimum() {
+        for symmetric in [true, false] {
+            let matrix = pseudo_random_matrix(10, symmetric);
+            let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+            let bounds = LowerBound::new(matrix);
+            
+            let one_tree = bounds.one_tree();
+            let (held_karp, _) = bounds.held_karp(optimum);
+            assert!(bounds.assignment() <= optimum + 1e-9);
+            assert!(one_tree <= held_karp + 1e-9);
+            assert!(held_karp <= optimum + 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_assignment_allows_subtours() {
+        // Two 2-cycles {0, 1} and {2, 3} cost 6, while every tour costs at least 21
+        let matrix = vec![
+            vec![0.0, 1.0, 9.0, 9.0],
+            vec![1.0, 0.0, 9.0, 9.0],
+            vec![9.0, 9.0, 0.0, 2.0],
+            vec![9.0, 9.0, 2.0, 0.0],
+        ];
+        assert_eq!(LowerBound::new(matrix).assignment(), 6.0);
+    }
+    
+    #[test]
+    fn test_budgets() {
+        let matrix = pseudo_random_matrix(30, true);
+        let bounds = LowerBound::new(matrix.clone()).with_time_limit(Some(Duration::ZERO));
+        assert_eq!(bounds.held_karp(1e6).0, bounds.one_tree());
+        assert!(bounds.assignment_pays_off());
+        
+        assert!(!LowerBound::new(pseudo_random_matrix(101, true)).assignment_pays_off());
+        assert!(LowerBound::new(pseudo_random_matrix(101, false)).assignment_pays_off());
+    }
+}
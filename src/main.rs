@@ -1,14 +1,50 @@
-use clap::Parser;
+use clap::{Arg, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use clap::builder::PossibleValuesParser;
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::IsTerminal;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use anyhow::{Result, Context};
 use thiserror::Error;
 
 mod tsp_solver;
+mod solver;
+mod cancel;
+mod progress;
+mod branch_and_bound;
+mod heuristics;
+mod local_search;
+mod lin_kernighan;
+mod simulated_annealing;
+mod genetic;
+mod ant_colony;
+mod christofides;
+mod matching;
+mod lower_bound;
+mod open_path;
+mod time_windows;
+mod vehicle_routing;
+mod multi_salesman;
+mod prize_collecting;
+mod rng;
 mod input_parser;
 mod visualizer;
+#[cfg(test)]
+mod test_support;
 
-use tsp_solver::TSPSolver;
+use cancel::CancelToken;
+use progress::{ProgressBar, ProgressObserver};
+use solver::{Listing, Options, Problem, Solution, Solver, Status, HELD_KARP, ALGORITHMS};
+use tsp_solver::{Route, TieBreak, TourObjective, bottleneck_edge, from_start, route_cost, tour_cost};
+use local_search::LocalSearch;
+use lower_bound::{gap_percent, LowerBound};
+use open_path::PathAsTour;
+use time_windows::{Schedule, TimeWindow, TimeWindowSolver};
+use vehicle_routing::{Demands, Split, VehicleRouting};
+use multi_salesman::{MultiSalesman, Objective};
+use prize_collecting::{PrizeCollecting, PrizeObjective};
 use input_parser::InputParser;
 use visualizer::Visualizer;
 
@@ -17,8 +53,8 @@ use visualizer::Visualizer;
 #[command(about = "A TSP solver using dynamic programming")]
 struct Args {
     /// Input file name
-    #[arg(short, long)]
-    input: String,
+    #[arg(short, long, required_unless_present = "list_algorithms")]
+    input: Option<String>,
     
     /// Output file name
     #[arg(short, long, default_value = "tsp_solution")]
@@ -27,6 +63,126 @@ struct Args {
     /// Show steps
     #[arg(short, long)]
     verbose: bool,
+    
+    /// Worker threads for the DP (0 = all cores)
+    #[arg(short, long, default_value_t = 1)]
+    threads: usize,
+    
+    /// Solving algorithm, by name or alias (see --list-algorithms)
+    #[arg(short, long, default_value = HELD_KARP)]
+    algorithm: String,
+    
+    /// List the available algorithms and exit
+    #[arg(long)]
+    list_algorithms: bool,
+    
+    /// Improve the tour with 2-opt and Or-opt local search
+    #[arg(short, long)]
+    local_search: bool,
+    
+    /// Also use 3-opt moves in local search (implies --local-search)
+    #[arg(long)]
+    three_opt: bool,
+    
+    /// Start from a tour file in input/ instead of running the algorithm
+    #[arg(long)]
+    initial_tour: Option<String>,
+    
+    /// Random seed for metaheuristics
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+    
+    /// Iteration budget for metaheuristics (default 1000000 for annealing, 500 for ant colony)
+    #[arg(long)]
+    iterations: Option<usize>,
+    
+    /// Time budget in seconds: heuristics return their best tour so far, exact tour solvers
+    /// that run out fall back to a heuristic tour with a lower bound
+    #[arg(long)]
+    time_limit: Option<f64>,
+    
+    /// Time budget in seconds for the Held-Karp lower bound that certifies heuristic tours
+    #[arg(long, value_name = "SECONDS", default_value_t = 1.0)]
+    bound_time: f64,
+    
+    /// City the route starts from (default: the first city in the input)
+    #[arg(long, value_name = "CITY")]
+    start: Option<String>,
+    
+    /// Find a Hamiltonian path from the start city instead of a closed tour
+    #[arg(long)]
+    open: bool,
+    
+    /// City where the open path must end (implies --open)
+    #[arg(long, value_name = "CITY")]
+    end: Option<String>,
+    
+    /// Find a Hamiltonian path with both endpoints free
+    #[arg(long, conflicts_with_all = ["open", "end", "start"])]
+    free_start: bool,
+    
+    /// How customers are split into vehicle routes when the input has demands
+    #[arg(long, value_enum, default_value_t = SplitArg::Savings)]
+    split: SplitArg,
+    
+    /// Number of salesmen starting and ending at the start city (mTSP when above 1)
+    #[arg(long, default_value_t = 1)]
+    salesmen: usize,
+    
+    /// What to optimise (`longest` needs several salesmen, `bottleneck` and `max-total` held-karp)
+    #[arg(long, value_enum, default_value_t = ObjectiveArg::Total)]
+    objective: ObjectiveArg,
+    
+    /// Travel budget for orienteering (needs PRIZES in the input; without it skipped
+    /// prizes count as penalties)
+    #[arg(long, value_name = "DISTANCE")]
+    budget: Option<f64>,
+    
+    /// List the K best distinct tours (held-karp only)
+    #[arg(long, value_name = "K")]
+    k_best: Option<usize>,
+    
+    /// How to choose between equally good tours (held-karp only)
+    #[arg(long, value_enum, default_value_t = TieBreakArg::Loop)]
+    tie_break: TieBreakArg,
+    
+    /// Count the optimal tours and list up to CAP of them (held-karp only)
+    #[arg(long, value_name = "CAP", conflicts_with = "k_best")]
+    all_optimal: Option<usize>,
+    
+    /// Algorithm parameters given on the command line (see `command`)
+    #[arg(skip)]
+    params: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum TieBreakArg {
+    /// Whichever tour the DP finds first
+    Loop,
+    /// Alphabetically first city at every step
+    Name,
+    /// Shortest first leg, then alphabetical
+    FirstLeg,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ObjectiveArg {
+    /// Sum of all route lengths
+    Total,
+    /// Length of the longest route (min-max)
+    Longest,
+    /// Length of the longest single edge (bottleneck TSP)
+    Bottleneck,
+    /// Maximise the total distance (max TSP)
+    MaxTotal,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SplitArg {
+    /// Clarke-Wright savings
+    Savings,
+    /// Sweep around the depot
+    Sweep,
 }
 
 #[derive(Error, Debug)]
@@ -42,16 +198,33 @@ pub enum TSPError {
     
     #[error("Solver error: {0}")]
     SolverError(String),
+    
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let matches = command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    for algorithm in ALGORITHMS {
+        for parameter in algorithm.parameters() {
+            if let Some(value) = matches.get_one::<String>(parameter.name) {
+                args.params.insert(parameter.name.to_string(), value.clone());
+            }
+        }
+    }
+    
+    if args.list_algorithms {
+        print_algorithms();
+        return Ok(());
+    }
+    let algorithm = solver::find(&args.algorithm)?;
     
     println!("TSP Solver with Dynamic Programming");
     println!("=====================================");
     
     // Validate input file path
-    let input_path = format!("input/{}", args.input);
+    let input_path = format!("input/{}", args.input.as_deref().unwrap_or_default());
     if !Path::new(&input_path).exists() {
         return Err(TSPError::FileNotFound(input_path.clone()).into());
     }
@@ -68,29 +241,237 @@ fn main() -> Result<()> {
     
     println!("Successfully parsed {} cities", cities.len());
     
-    // Validate input
+    let windows = parser.parse_time_windows(&content, &cities)
+        .map_err(|e| TSPError::InvalidFormat(e.to_string()))?;
+    let demands = parser.parse_demands(&content, &cities)
+        .map_err(|e| TSPError::InvalidFormat(e.to_string()))?;
+    let precedence = parser.parse_precedence(&content, &cities)
+        .map_err(|e| TSPError::InvalidFormat(e.to_string()))?;
+    let prizes = parser.parse_prizes(&content, &cities)
+        .map_err(|e| TSPError::InvalidFormat(e.to_string()))?;
+    let clusters = parser.parse_clusters(&content, &cities)
+        .map_err(|e| TSPError::InvalidFormat(e.to_string()))?;
+    
+    // Validate input (vehicle routes and prize tours have their own solvers, so the
+    // algorithm limit does not apply; the clustered DP grows with the number of clusters)
+    if demands.is_none() && prizes.is_none() {
+        let size = clusters.as_ref().map_or(cities.len(), |c| c.iter().max().map_or(0, |&id| id + 1));
+        validate_size(size, algorithm)?;
+    }
     validate_input(&cities, &distance_matrix)?;
     
     if args.verbose {
         print_input_summary(&cities, &distance_matrix);
     }
     
-    // Solve TSP using dynamic programming
-    println!("Solving TSP using Dynamic Programming...");
-    let mut solver = TSPSolver::new(distance_matrix);
-    let (min_cost, path) = solver.solve(args.verbose)?;
+    let start = args.start.as_deref().map(|name| city_index(&cities, name)).transpose()?.unwrap_or(0);
+    let route = resolve_route(&args, &cities, start)?;
+    
+    if windows.is_some() {
+        validate_time_window_options(&args, algorithm, route)?;
+    }
+    let other_modes = windows.is_some() || demands.is_some() || prizes.is_some();
+    if precedence.is_some() {
+        validate_dp_only_options(&args, algorithm, "Precedence constraints", other_modes || clusters.is_some())?;
+    }
+    if clusters.is_some() {
+        validate_dp_only_options(&args, algorithm, "Clusters", other_modes)?;
+    }
+    
+    let objective = match args.objective {
+        ObjectiveArg::Longest if args.salesmen == 1 => {
+            return Err(TSPError::GraphValidation(
+                "--objective longest compares routes and needs --salesmen above 1".to_string()
+            ).into());
+        }
+        ObjectiveArg::Total | ObjectiveArg::Longest => TourObjective::MinTotal,
+        ObjectiveArg::Bottleneck => TourObjective::MinBottleneck,
+        ObjectiveArg::MaxTotal => TourObjective::MaxTotal,
+    };
+    if objective != TourObjective::MinTotal {
+        validate_dp_only_options(&args, algorithm, "Bottleneck and max-total objectives", other_modes)?;
+    }
+    if args.k_best.is_some() {
+        validate_dp_only_options(&args, algorithm, "Alternative tours", other_modes)?;
+    }
+    if args.all_optimal.is_some() {
+        validate_dp_only_options(&args, algorithm, "Co-optimal tour listings", other_modes)?;
+    }
+    if args.tie_break != TieBreakArg::Loop {
+        // The time-window DP breaks its own ties
+        validate_dp_only_options(&args, algorithm, "Tie-break policies", demands.is_some() || prizes.is_some())?;
+    }
+    
+    if let Some(demands) = demands {
+        if !route.is_closed() || windows.is_some() || prizes.is_some() || args.initial_tour.is_some() {
+            return Err(TSPError::GraphValidation(
+                "Vehicle routing does not support open paths, time windows, prizes or an initial tour".to_string()
+            ).into());
+        }
+        return solve_vehicle_routing(&args, algorithm, &cities, &distance_matrix, demands, start);
+    }
+    
+    if let Some(prizes) = prizes {
+        if !route.is_closed() || windows.is_some() || args.salesmen > 1 || args.initial_tour.is_some() {
+            return Err(TSPError::GraphValidation(
+                "Prize collecting does not support open paths, time windows, multiple salesmen or an initial tour".to_string()
+            ).into());
+        }
+        return solve_prize_collecting(&args, algorithm, &cities, &distance_matrix, prizes, start);
+    } else if args.budget.is_some() {
+        return Err(TSPError::InvalidFormat(
+            "--budget needs a PRIZES section in the input".to_string()
+        ).into());
+    }
+    
+    if args.salesmen > 1 {
+        if !route.is_closed() || windows.is_some() || args.initial_tour.is_some() {
+            return Err(TSPError::GraphValidation(
+                "Multiple salesmen do not support open paths, time windows or an initial tour".to_string()
+            ).into());
+        }
+        return solve_multi_salesman(&args, algorithm, &cities, &distance_matrix, start);
+    }
+    
+    // Local search and the lower bounds see open paths as tours through an extra city
+    let open_path = (!route.is_closed()).then(|| PathAsTour::new(&distance_matrix, start, route));
+    let solver_matrix = open_path.as_ref().map_or_else(|| distance_matrix.clone(), |p| p.matrix.clone());
+    
+    // Solve TSP
+    let cancel = cancel_token(&args);
+    let started = Instant::now();
+    let solution = if let Some(tour_file) = &args.initial_tour {
+        let tour_path = format!("input/{}", tour_file);
+        println!("Reading initial tour: {}", tour_path);
+        let content = fs::read_to_string(&tour_path)
+            .map_err(|_| TSPError::FileNotFound(tour_path.clone()))?;
+        let mut path = parser.parse_tour(&content, &cities)
+            .map_err(|e| TSPError::InvalidFormat(e.to_string()))?;
+        if let Some(instance) = &open_path {
+            path = instance.to_path(&instance.to_tour(&path));
+        }
+        Solution::new((route_cost(&distance_matrix, &path, route), path), Status::Heuristic, started)
+    } else if let Some(windows) = &windows {
+        println!("Solving TSP with time windows using {}...", algorithm.label());
+        let instance = TimeWindowSolver::new(distance_matrix.clone(), windows.clone())
+            .with_start(start)
+            .with_tie_break(tie_break(args.tie_break, &cities));
+        algorithm.solve_time_windows(&instance, &solver_options(&args, &cancel))?
+    } else {
+        println!("Solving TSP using {}...", algorithm.label());
+        let problem = Problem {
+            start,
+            route,
+            objective,
+            precedence: precedence.unwrap_or_default(),
+            clusters: clusters.clone().unwrap_or_default(),
+            tie_break: tie_break(args.tie_break, &cities),
+            listing: match (args.k_best, args.all_optimal) {
+                (Some(k), _) => Listing::KBest(k),
+                (None, Some(cap)) => Listing::CoOptimal(cap),
+                (None, None) => Listing::Best,
+            },
+            names: cities.clone(),
+            ..Problem::new(distance_matrix.clone())
+        };
+        algorithm.solve(&problem, &solver_options(&args, &cancel))?
+    };
+    let Solution { cost: mut min_cost, tour: mut path, status, bound, elapsed, stats, trace, alternatives, optimal_count } = solution;
+    
+    // Improve tour
+    if args.local_search || args.three_opt {
+        println!("Improving tour with local search...");
+        let search = LocalSearch::new(solver_matrix.clone()).with_three_opt(args.three_opt);
+        match &open_path {
+            Some(instance) => {
+                let mut tour = instance.to_tour(&path);
+                search.improve(&mut tour, args.verbose);
+                path = instance.to_path(&tour);
+                min_cost = route_cost(&distance_matrix, &path, route);
+            }
+            None => min_cost = search.improve(&mut path, args.verbose),
+        }
+    }
+    
+    // Report closed tours from the chosen start city
+    let path = from_start(path, start, route);
+    
+    // Certify shortest-tour results the solver has no matching bound for; lower bounds
+    // on the tour length say nothing about the other objectives
+    let proven = bound.is_some_and(|bound| min_cost - bound <= 1e-9 * min_cost.abs().max(1.0));
+    let lower_bound = if proven || objective != TourObjective::MinTotal {
+        None
+    } else {
+        println!("Computing lower bounds...");
+        let offset = open_path.as_ref().map_or(0.0, |p| p.offset());
+        let time_limit = Duration::from_secs_f64(args.bound_time.max(0.0));
+        Some(best_lower_bound(&solver_matrix, min_cost + offset, offset, time_limit))
+    };
     
     // Display results
     println!("\nSolution Found!");
     println!("==================");
-    println!("Minimum cost: {}", min_cost);
+    let total_distance = route_cost(&distance_matrix, &path, route);
+    match (objective, lower_bound) {
+        (TourObjective::MinBottleneck, _) => println!("Bottleneck: {} (optimal, total distance {:.1})",
+                                                      min_cost, total_distance),
+        (TourObjective::MaxTotal, _) => println!("Maximum cost: {} (optimal)", min_cost),
+        (_, Some((name, bound))) => println!("Minimum cost: {} (lower bound {:.1} from {}, gap {:.2}%)",
+                                             min_cost, bound, name, gap_percent(min_cost, bound)),
+        (_, None) => println!("Minimum cost: {} (optimal)", min_cost),
+    }
+    if status == Status::Stopped {
+        match lower_bound {
+            Some((_, bound)) if min_cost - bound <= 1e-9 * min_cost.abs().max(1.0) => {
+                println!("Status: optimal, matched by the lower bound (search stopped at the time limit)");
+            }
+            _ => println!("Status: not proven optimal (time limit reached)"),
+        }
+    }
+    let counts: Vec<String> = stats.iter().map(|(name, value)| format!("{} {}", value, name)).collect();
+    if counts.is_empty() {
+        println!("Solver time: {:.2?}", elapsed);
+    } else {
+        println!("Solver time: {:.2?} ({})", elapsed, counts.join(", "));
+    }
     print!("Optimal path: ");
     for (i, &city_idx) in path.iter().enumerate() {
         if i > 0 { print!(" -> "); }
         print!("{}", cities[city_idx]);
     }
-    print!(" -> {}", cities[path[0]]); // Return to start
+    if route.is_closed() {
+        print!(" -> {}", cities[path[0]]); // Return to start
+    }
     println!();
+    if objective == TourObjective::MinBottleneck {
+        if let Some((from, to)) = bottleneck_edge(&distance_matrix, &path, route) {
+            println!("Bottleneck edge: {} -> {} ({})", cities[from], cities[to], distance_matrix[from][to]);
+        }
+    }
+    if clusters.is_some() {
+        println!("Visited {} of {} cities (one per cluster)", path.len(), cities.len());
+    }
+    if args.k_best.is_some() {
+        println!("\n🏆 {} best tours:", alternatives.len());
+        for (rank, (cost, tour)) in alternatives.iter().enumerate() {
+            println!("  {:>2}. {:>10.1}  {}", rank + 1, cost, format_stops(&cities, tour, route));
+        }
+    }
+    if let Some(count) = optimal_count {
+        match count {
+            1 => println!("\n🤝 The optimal tour is unique"),
+            count => println!("\n🤝 {} optimal tours (showing {}):", count, alternatives.len()),
+        }
+        if count > 1 {
+            for (rank, (_, tour)) in alternatives.iter().enumerate() {
+                println!("  {:>2}. {}", rank + 1, format_stops(&cities, tour, route));
+            }
+        }
+    }
+    
+    if let Some(windows) = &windows {
+        print_schedule(&cities, windows, &Schedule::new(&distance_matrix, windows, &path));
+    }
     
     // Generate visualization
     println!("\nGenerating visualization...");
@@ -99,10 +480,17 @@ fn main() -> Result<()> {
     let output_filename = generate_unique_filename(&args.output)?;
     
     let visualizer = Visualizer::new();
-    visualizer.create_visualization(&cities, &path, min_cost, &output_filename)
+    visualizer.create_visualization(&cities, &path, route.is_closed(), total_distance, &output_filename)
         .with_context(|| "Failed to create visualization")?;
     
     println!("Visualization saved to: {}", output_filename);
+    
+    if !trace.is_empty() {
+        let chart_filename = generate_unique_filename(&format!("{}_convergence", args.output))?;
+        visualizer.create_convergence_chart(&trace, algorithm.label(), algorithm.trace_series(), &chart_filename)
+            .with_context(|| "Failed to create convergence chart")?;
+        println!("Convergence chart saved to: {}", chart_filename);
+    }
     println!("\nTSP solving completed successfully!");
     
     Ok(())
@@ -142,6 +530,252 @@ fn generate_unique_filename(base_name: &str) -> Result<String> {
     }
 }
 
+/// Command-line parser: the fixed flags of `Args` plus one `--NAME VALUE` flag per
+/// algorithm parameter in the registry
+fn command() -> clap::Command {
+    let mut command = Args::command();
+    let mut seen = Vec::new();
+    for algorithm in ALGORITHMS {
+        for parameter in algorithm.parameters() {
+            if seen.contains(&parameter.name) {
+                continue;
+            }
+            seen.push(parameter.name);
+            let mut arg = Arg::new(parameter.name)
+                .long(parameter.name)
+                .value_name("VALUE")
+                .help(format!("{} [{}]", parameter.help, algorithm.name()));
+            if !parameter.choices.is_empty() {
+                arg = arg.value_parser(PossibleValuesParser::new(parameter.choices));
+            }
+            if let Some(default) = parameter.default {
+                arg = arg.default_value(default);
+            }
+            command = command.arg(arg);
+        }
+    }
+    command
+}
+
+/// Algorithm settings from the command line
+fn solver_options(args: &Args, cancel: &CancelToken) -> Options {
+    Options {
+        verbose: args.verbose,
+        threads: args.threads,
+        seed: args.seed,
+        iterations: args.iterations,
+        time_limit: args.time_limit.map(Duration::from_secs_f64),
+        cancel: cancel.clone(),
+        progress: progress_bar(),
+        local_search: args.local_search || args.three_opt,
+        params: args.params.clone(),
+    }
+}
+
+/// Stop signal for the tour solvers, raised once `--time-limit` has passed
+fn cancel_token(args: &Args) -> CancelToken {
+    CancelToken::new().with_time_limit(args.time_limit.map(Duration::from_secs_f64))
+}
+
+/// DP progress bar on stderr, unless it is redirected
+fn progress_bar() -> Option<Arc<dyn ProgressObserver>> {
+    std::io::stderr().is_terminal().then(|| Arc::new(ProgressBar::new()) as Arc<dyn ProgressObserver>)
+}
+
+fn print_algorithms() {
+    println!("Algorithms (select with --algorithm NAME):");
+    for algorithm in ALGORITHMS {
+        let mut name = algorithm.name().to_string();
+        if !algorithm.aliases().is_empty() {
+            name += &format!(" ({})", algorithm.aliases().join(", "));
+        }
+        let mut notes = Vec::new();
+        if algorithm.is_exact() {
+            notes.push("exact".to_string());
+        }
+        if let Some(max) = algorithm.max_cities() {
+            notes.push(format!("up to {} cities", max));
+        }
+        if algorithm.supports_time_windows() {
+            notes.push("time windows".to_string());
+        }
+        if !algorithm.supports_open_paths() {
+            notes.push("closed tours only".to_string());
+        }
+        if notes.is_empty() {
+            println!("  {:<26} {}", name, algorithm.description());
+        } else {
+            println!("  {:<26} {} [{}]", name, algorithm.description(), notes.join(", "));
+        }
+        for parameter in algorithm.parameters() {
+            let mut value = parameter.choices.join("|");
+            if let Some(default) = parameter.default {
+                value = if value.is_empty() { default.to_string() } else { format!("{}, default {}", value, default) };
+            }
+            if value.is_empty() {
+                println!("      --{}", parameter.name);
+            } else {
+                println!("      --{} ({})", parameter.name, value);
+            }
+        }
+    }
+}
+
+/// CVRP mode: split customers into routes from the start city that fit the capacity, then
+/// re-solve every route with the chosen algorithm
+fn solve_vehicle_routing(args: &Args, algorithm: &dyn Solver, cities: &[String], distance_matrix: &[Vec<f64>],
+                         demands: Demands, depot: usize) -> Result<()> {
+    let split = match args.split {
+        SplitArg::Savings => Split::Savings,
+        SplitArg::Sweep => Split::Sweep,
+    };
+    
+    println!("Solving vehicle routing from {} (capacity {}) using {}...",
+             cities[depot], demands.capacity, algorithm.label());
+    let vrp = VehicleRouting::new(distance_matrix.to_vec(), demands)
+        .with_depot(depot)
+        .with_split(split);
+    let (total_cost, routes) = algorithm.solve_vehicle_routing(&vrp, &solver_options(args, &cancel_token(args)))?;
+    
+    println!("\nSolution Found!");
+    println!("==================");
+    println!("Total cost: {} ({} routes)", total_cost, routes.len());
+    for (i, route) in routes.iter().enumerate() {
+        let stops: Vec<&str> = route.iter()
+            .chain(&route[..1]) // Return to depot
+            .map(|&c| cities[c].as_str())
+            .collect();
+        println!("Route {} (load {}, cost {:.1}): {}",
+                i + 1, vrp.load(&route[1..]), tour_cost(distance_matrix, route), stops.join(" -> "));
+    }
+    
+    println!("\nGenerating visualization...");
+    let output_filename = generate_unique_filename(&args.output)?;
+    let title = format!("Vehicle Routes - Total Distance: {:.1}", total_cost);
+    Visualizer::new().create_routes_visualization(cities, &routes, &title, &output_filename)
+        .with_context(|| "Failed to create visualization")?;
+    println!("Visualization saved to: {}", output_filename);
+    
+    println!("\nTSP solving completed successfully!");
+    Ok(())
+}
+
+/// Prize modes: orienteering with `--budget`, otherwise skipped prizes are penalties.
+/// Held-Karp solves small inputs exactly; everything else uses greedy insertion.
+fn solve_prize_collecting(args: &Args, algorithm: &dyn Solver, cities: &[String], distance_matrix: &[Vec<f64>],
+                          prizes: Vec<f64>, start: usize) -> Result<()> {
+    let objective = match args.budget {
+        Some(budget) => PrizeObjective::Orienteering { budget },
+        None => PrizeObjective::Penalties,
+    };
+    
+    match objective {
+        PrizeObjective::Orienteering { budget } => {
+            println!("Solving orienteering from {} (budget {}) using {}...", cities[start], budget, algorithm.label());
+        }
+        PrizeObjective::Penalties => {
+            println!("Solving prize-collecting TSP from {} using {}...", cities[start], algorithm.label());
+        }
+    }
+    let solver = PrizeCollecting::new(distance_matrix.to_vec(), prizes.clone())
+        .with_start(start)
+        .with_objective(objective);
+    let (length, tour) = algorithm.solve_prizes(&solver, &solver_options(args, &cancel_token(args)))?;
+    
+    println!("\nSolution Found!");
+    println!("==================");
+    match objective {
+        PrizeObjective::Orienteering { budget } => println!("Tour length: {:.1} (budget {})", length, budget),
+        PrizeObjective::Penalties => {
+            let penalty = solver.penalty(&tour);
+            println!("Cost: {:.1} (tour {:.1} + penalties {:.1})", length + penalty, length, penalty);
+        }
+    }
+    println!("Collected prize: {} of {}", solver.prize(&tour), prizes.iter().sum::<f64>());
+    
+    let stops: Vec<&str> = tour.iter()
+        .chain(&tour[..1]) // Return to start
+        .map(|&c| cities[c].as_str())
+        .collect();
+    println!("Tour: {}", stops.join(" -> "));
+    let skipped: Vec<&str> = (0..cities.len())
+        .filter(|c| !tour.contains(c))
+        .map(|c| cities[c].as_str())
+        .collect();
+    if !skipped.is_empty() {
+        println!("Skipped: {}", skipped.join(", "));
+    }
+    
+    println!("\nGenerating visualization...");
+    let output_filename = generate_unique_filename(&args.output)?;
+    Visualizer::new().create_visualization(cities, &tour, true, length, &output_filename)
+        .with_context(|| "Failed to create visualization")?;
+    println!("Visualization saved to: {}", output_filename);
+    
+    println!("\nTSP solving completed successfully!");
+    Ok(())
+}
+
+/// mTSP mode: several closed routes from the start city that together visit every city.
+/// Held-Karp runs the exact subset DP; other algorithms solve one tour through copies of
+/// the depot and cut it into routes.
+fn solve_multi_salesman(args: &Args, algorithm: &dyn Solver, cities: &[String], distance_matrix: &[Vec<f64>],
+                        depot: usize) -> Result<()> {
+    let objective = match args.objective {
+        ObjectiveArg::Longest => Objective::LongestRoute,
+        _ => Objective::TotalDistance,
+    };
+    
+    println!("Solving for {} salesmen from {} using {}...", args.salesmen, cities[depot], algorithm.label());
+    let mtsp = MultiSalesman::new(distance_matrix.to_vec(), args.salesmen)
+        .with_depot(depot)
+        .with_objective(objective);
+    let (score, routes) = algorithm.solve_salesmen(&mtsp, &solver_options(args, &cancel_token(args)))?;
+    
+    let lengths: Vec<f64> = routes.iter().map(|r| tour_cost(distance_matrix, r)).collect();
+    let total_cost: f64 = lengths.iter().sum();
+    
+    println!("\nSolution Found!");
+    println!("==================");
+    match objective {
+        Objective::TotalDistance => println!("Total cost: {} ({} routes)", score, routes.len()),
+        Objective::LongestRoute => println!("Longest route: {:.1} (total cost {:.1})", score, total_cost),
+    }
+    for (i, route) in routes.iter().enumerate() {
+        let stops: Vec<&str> = route.iter()
+            .chain(&route[..1]) // Return to depot
+            .map(|&c| cities[c].as_str())
+            .collect();
+        println!("Route {} (cost {:.1}): {}", i + 1, lengths[i], stops.join(" -> "));
+    }
+    
+    println!("\nGenerating visualization...");
+    let output_filename = generate_unique_filename(&args.output)?;
+    let title = match objective {
+        Objective::TotalDistance => format!("Salesman Routes - Total Distance: {:.1}", score),
+        Objective::LongestRoute => format!("Salesman Routes - Longest Route: {:.1}", score),
+    };
+    Visualizer::new().create_routes_visualization(cities, &routes, &title, &output_filename)
+        .with_context(|| "Failed to create visualization")?;
+    println!("Visualization saved to: {}", output_filename);
+    
+    println!("\nTSP solving completed successfully!");
+    Ok(())
+}
+
+/// Check maximum number of cities (for performance)
+fn validate_size(n: usize, algorithm: &dyn Solver) -> Result<()> {
+    if let Some(max) = algorithm.max_cities() {
+        if n > max {
+            return Err(TSPError::GraphValidation(
+                format!("Maximum {} cities supported by {} (due to exponential complexity)",
+                       max, algorithm.label())
+            ).into());
+        }
+    }
+    Ok(())
+}
+
 fn validate_input(cities: &[String], matrix: &[Vec<f64>]) -> Result<()> {
     let n = cities.len();
     
@@ -152,13 +786,6 @@ fn validate_input(cities: &[String], matrix: &[Vec<f64>]) -> Result<()> {
         ).into());
     }
     
-    // Check maximum number of cities (for performance)
-    if n > 20 {
-        return Err(TSPError::GraphValidation(
-            "Maximum 20 cities supported (due to exponential complexity)".to_string()
-        ).into());
-    }
-    
     // Check matrix dimensions
     if matrix.len() != n {
         return Err(TSPError::GraphValidation(
@@ -196,6 +823,142 @@ fn validate_input(cities: &[String], matrix: &[Vec<f64>]) -> Result<()> {
     Ok(())
 }
 
+/// Closed tour unless --open, --end or --free-start asks for a Hamiltonian path
+fn resolve_route(args: &Args, cities: &[String], start: usize) -> Result<Route> {
+    if args.free_start {
+        return Ok(Route::FreePath);
+    }
+    
+    match &args.end {
+        Some(name) => {
+            let end = city_index(cities, name)?;
+            if end == start {
+                return Err(TSPError::GraphValidation(
+                    format!("End city '{}' is also the start city", name)
+                ).into());
+            }
+            Ok(Route::Path { end: Some(end) })
+        }
+        None if args.open => Ok(Route::Path { end: None }),
+        None => Ok(Route::Tour),
+    }
+}
+
+/// Time windows need a closed tour and a solver that checks them
+fn validate_time_window_options(args: &Args, algorithm: &dyn Solver, route: Route) -> Result<()> {
+    let problem = if !route.is_closed() {
+        Some("open paths".to_string())
+    } else if args.local_search || args.three_opt {
+        Some("local search".to_string())
+    } else if args.initial_tour.is_none() && !algorithm.supports_time_windows() {
+        Some(algorithm.label().to_string())
+    } else {
+        None
+    };
+    
+    match problem {
+        Some(what) => Err(TSPError::GraphValidation(
+            format!("Time windows are not supported with {} (use held-karp or cheapest-insertion)", what)
+        ).into()),
+        None => Ok(()),
+    }
+}
+
+/// Precedence pairs and clusters are only enforced by the DP, and nothing may reorder its
+/// tour afterwards
+fn validate_dp_only_options(args: &Args, algorithm: &dyn Solver, feature: &str, other_modes: bool) -> Result<()> {
+    let problem = if other_modes || args.salesmen > 1 {
+        Some("other input sections or multiple salesmen")
+    } else if args.local_search || args.three_opt {
+        Some("local search")
+    } else if args.initial_tour.is_some() {
+        Some("an initial tour")
+    } else if !algorithm.supports_constraints() {
+        Some(algorithm.label())
+    } else {
+        None
+    };
+    
+    match problem {
+        Some(what) => Err(TSPError::GraphValidation(
+            format!("{} are not supported with {} (use held-karp)", feature, what)
+        ).into()),
+        None => Ok(()),
+    }
+}
+
+/// Tie-break policy of the DP; city names give the lexicographic ranks
+fn tie_break(policy: TieBreakArg, cities: &[String]) -> TieBreak {
+    let mut ranks = vec![0; cities.len()];
+    let mut by_name: Vec<usize> = (0..cities.len()).collect();
+    by_name.sort_by(|&a, &b| cities[a].cmp(&cities[b]));
+    for (rank, &city) in by_name.iter().enumerate() {
+        ranks[city] = rank;
+    }
+    
+    match policy {
+        TieBreakArg::Loop => TieBreak::LoopOrder,
+        TieBreakArg::Name => TieBreak::Lexicographic(ranks),
+        TieBreakArg::FirstLeg => TieBreak::ShortestFirstLeg(ranks),
+    }
+}
+
+/// "A -> B -> C", back to the first city for closed tours
+fn format_stops(cities: &[String], tour: &[usize], route: Route) -> String {
+    let mut stops: Vec<&str> = tour.iter().map(|&c| cities[c].as_str()).collect();
+    if route.is_closed() {
+        stops.push(&cities[tour[0]]); // Return to start
+    }
+    stops.join(" -> ")
+}
+
+fn city_index(cities: &[String], name: &str) -> Result<usize> {
+    cities.iter().position(|c| c == name).ok_or_else(|| {
+        TSPError::GraphValidation(
+            format!("Unknown city '{}' (cities: {})", name, cities.join(", "))
+        ).into()
+    })
+}
+
+/// Strongest of the assignment (asymmetric or small matrices only), 1-tree and Held-Karp
+/// bounds, with its name; `offset` is a constant every tour of `matrix` carries and is
+/// taken off the bounds
+fn best_lower_bound(matrix: &[Vec<f64>], upper_bound: f64, offset: f64, time_limit: Duration) -> (&'static str, f64) {
+    let bounds = LowerBound::new(matrix.to_vec()).with_time_limit(Some(time_limit));
+    let mut candidates = Vec::with_capacity(3);
+    if bounds.assignment_pays_off() {
+        candidates.push(("assignment", bounds.assignment() - offset));
+    }
+    candidates.push(("1-tree", bounds.one_tree() - offset));
+    candidates.push(("Held-Karp", bounds.held_karp(upper_bound).0 - offset));
+    
+    for (name, bound) in &candidates {
+        println!("  • {} bound: {:.1}", name, bound);
+    }
+    
+    candidates.into_iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap()
+}
+
+fn print_schedule(cities: &[String], windows: &[TimeWindow], schedule: &Schedule) {
+    println!("\n🕒 Schedule:");
+    for stop in &schedule.stops {
+        let window = &windows[stop.city];
+        println!("  {:<12} arrive {:8.1}  start {:8.1}  depart {:8.1}  window [{:.1}, {:.1}]",
+                cities[stop.city], stop.arrival, stop.start, stop.departure, window.ready, window.due);
+    }
+    if let Some(depot) = schedule.stops.first() {
+        println!("  {:<12} arrive {:8.1}  (return)", cities[depot.city], schedule.return_time);
+    }
+    
+    let violations = schedule.violations(windows);
+    if violations.is_empty() {
+        println!("✅ All time windows met");
+    }
+    for (city, time, due) in violations {
+        println!("⚠️  {} is served at {:.1}, after its window closes at {:.1}", cities[city], time, due);
+    }
+}
+
 fn print_input_summary(cities: &[String], matrix: &[Vec<f64>]) {
     println!("\n📋 Input Summary:");
     println!("Cities: {:?}", cities);
@@ -0,0 +1,629 @@
+/// Integer weights are scaled so the largest cost maps to this value; keeps the
+/// blossom duals exact while losing at most 1e-9 relative precision
+const WEIGHT_SCALE: f64 = 1e9;
+
+/// Minimum-cost perfect matching on the complete graph given by `cost` (even size);
+/// returns the partner of every vertex
+pub fn min_cost_perfect_matching(cost: &[Vec<f64>]) -> Vec<usize> {
+    Matching::min_cost(cost).solve(true)
+        .into_iter()
+        .map(|mate| mate.expect("complete graph of even size has a perfect matching"))
+        .collect()
+}
+
+/// Edmonds' blossom algorithm for maximum-weight matching in O(n³), with the
+/// primal-dual bookkeeping of Galil's "Efficient algorithms for finding maximum
+/// matchings in graphs". Vertices are 0..n, blossoms n..2n; an endpoint p refers
+/// to vertex `endpoint[p]` of edge p / 2, and p ^ 1 is the other end.
+struct Matching {
+    n: usize,
+    edges: Vec<(usize, usize, i64)>,
+    endpoint: Vec<usize>,
+    /// Endpoints p of the edges at every vertex, with `endpoint[p]` the far end
+    neighbour_ends: Vec<Vec<usize>>,
+    /// Matched endpoint per vertex
+    mate: Vec<Option<usize>>,
+    /// 0 = free, 1 = S, 2 = T (bit 4 marks blossoms seen by `scan_blossom`)
+    label: Vec<u8>,
+    /// Endpoint through which a vertex or blossom got its label
+    label_end: Vec<Option<usize>>,
+    /// Top-level blossom containing every vertex
+    in_blossom: Vec<usize>,
+    blossom_parent: Vec<Option<usize>>,
+    blossom_children: Vec<Vec<usize>>,
+    blossom_base: Vec<Option<usize>>,
+    /// Endpoints of the edges joining consecutive children of a blossom
+    blossom_endpoints: Vec<Vec<usize>>,
+    /// Least-slack edge to an S-vertex or S-blossom
+    best_edge: Vec<Option<usize>>,
+    /// Least-slack edges from an S-blossom to every other S-blossom
+    blossom_best_edges: Vec<Option<Vec<usize>>>,
+    unused_blossoms: Vec<usize>,
+    dual: Vec<i64>,
+    /// Edges known to have zero slack in the current stage
+    allowed: Vec<bool>,
+    queue: Vec<usize>,
+    /// Blossoms formed and expanded so far
+    formed: usize,
+    expanded: usize,
+}
+
+impl Matching {
+    /// Minimum cost = maximum of (largest - cost) over maximum-cardinality matchings
+    fn min_cost(cost: &[Vec<f64>]) -> Self {
+        let n = cost.len();
+        let largest = cost.iter().flatten().cloned().fold(0.0, f64::max);
+        let scale = if largest > 0.0 { WEIGHT_SCALE / largest } else { 1.0 };
+        
+        let mut edges = Vec::with_capacity(n * n.saturating_sub(1) / 2);
+        for (i, row) in cost.iter().enumerate() {
+            for (j, &c) in row.iter().enumerate().skip(i + 1) {
+                edges.push((i, j, ((largest - c) * scale).round() as i64));
+            }
+        }
+        Self::new(n, edges)
+    }
+    
+    fn new(n: usize, edges: Vec<(usize, usize, i64)>) -> Self {
+        let max_weight = edges.iter().map(|&(_, _, w)| w).max().unwrap_or(0).max(0);
+        
+        let mut endpoint = Vec::with_capacity(2 * edges.len());
+        let mut neighbour_ends = vec![Vec::new(); n];
+        for (k, &(i, j, _)) in edges.iter().enumerate() {
+            endpoint.push(i);
+            endpoint.push(j);
+            neighbour_ends[i].push(2 * k + 1);
+            neighbour_ends[j].push(2 * k);
+        }
+        
+        let edge_count = edges.len();
+        Self {
+            n,
+            edges,
+            endpoint,
+            neighbour_ends,
+            mate: vec![None; n],
+            label: vec![0; 2 * n],
+            label_end: vec![None; 2 * n],
+            in_blossom: (0..n).collect(),
+            blossom_parent: vec![None; 2 * n],
+            blossom_children: vec![Vec::new(); 2 * n],
+            blossom_base: (0..n).map(Some).chain((0..n).map(|_| None)).collect(),
+            blossom_endpoints: vec![Vec::new(); 2 * n],
+            best_edge: vec![None; 2 * n],
+            blossom_best_edges: vec![None; 2 * n],
+            unused_blossoms: (n..2 * n).collect(),
+            dual: (0..2 * n).map(|v| if v < n { max_weight } else { 0 }).collect(),
+            allowed: vec![false; edge_count],
+            queue: Vec::new(),
+            formed: 0,
+            expanded: 0,
+        }
+    }
+    
+    /// Maximum-weight matching; with `max_cardinality`, the heaviest among the largest matchings
+    fn solve(&mut self, max_cardinality: bool) -> Vec<Option<usize>> {
+        let n = self.n;
+        
+        for _ in 0..n {
+            // Setiap stage mencari satu augmenting path
+            self.label.iter_mut().for_each(|l| *l = 0);
+            self.best_edge.iter_mut().for_each(|e| *e = None);
+            self.blossom_best_edges[n..].iter_mut().for_each(|e| *e = None);
+            self.allowed.iter_mut().for_each(|a| *a = false);
+            self.queue.clear();
+            
+            for v in 0..n {
+                if self.mate[v].is_none() && self.label[self.in_blossom[v]] == 0 {
+                    self.assign_label(v, 1, None);
+                }
+            }
+            
+            let mut augmented = false;
+            loop {
+                while !augmented {
+                    let Some(v) = self.queue.pop() else { break };
+                    
+                    for idx in 0..self.neighbour_ends[v].len() {
+                        let p = self.neighbour_ends[v][idx];
+                        let k = p / 2;
+                        let w = self.endpoint[p];
+                        if self.in_blossom[v] == self.in_blossom[w] {
+                            continue;
+                        }
+                        
+                        let mut k_slack = 0;
+                        if !self.allowed[k] {
+                            k_slack = self.slack(k);
+                            if k_slack <= 0 {
+                                self.allowed[k] = true;
+                            }
+                        }
+                        
+                        if self.allowed[k] {
+                            if self.label[self.in_blossom[w]] == 0 {
+                                self.assign_label(w, 2, Some(p ^ 1));
+                            } else if self.label[self.in_blossom[w]] == 1 {
+                                match self.scan_blossom(v, w) {
+                                    Some(base) => self.add_blossom(base, k),
+                                    None => {
+                                        self.augment_matching(k);
+                                        augmented = true;
+                                        break;
+                                    }
+                                }
+                            } else if self.label[w] == 0 {
+                                self.label[w] = 2;
+                                self.label_end[w] = Some(p ^ 1);
+                            }
+                        } else if self.label[self.in_blossom[w]] == 1 {
+                            let b = self.in_blossom[v];
+                            if self.best_edge[b].is_none_or(|e| k_slack < self.slack(e)) {
+                                self.best_edge[b] = Some(k);
+                            }
+                        } else if self.label[w] == 0
+                            && self.best_edge[w].is_none_or(|e| k_slack < self.slack(e)) {
+                            self.best_edge[w] = Some(k);
+                        }
+                    }
+                }
+                
+                if augmented {
+                    break;
+                }
+                
+                // No augmenting path with the current duals: pick the smallest dual change
+                let mut delta: Option<(u8, i64)> = None;
+                let mut delta_edge = None;
+                let mut delta_blossom = None;
+                
+                if !max_cardinality {
+                    delta = Some((1, *self.dual[..n].iter().min().unwrap()));
+                }
+                
+                for v in 0..n {
+                    if self.label[self.in_blossom[v]] == 0 {
+                        if let Some(e) = self.best_edge[v] {
+                            let d = self.slack(e);
+                            if delta.is_none_or(|(_, best)| d < best) {
+                                delta = Some((2, d));
+                                delta_edge = Some(e);
+                            }
+                        }
+                    }
+                }
+                
+                for b in 0..2 * n {
+                    if self.blossom_parent[b].is_none() && self.label[b] == 1 {
+                        if let Some(e) = self.best_edge[b] {
+                            let d = self.slack(e) / 2;
+                            if delta.is_none_or(|(_, best)| d < best) {
+                                delta = Some((3, d));
+                                delta_edge = Some(e);
+                            }
+                        }
+                    }
+                }
+                
+                for b in n..2 * n {
+                    if self.blossom_base[b].is_some()
+                        && self.blossom_parent[b].is_none()
+                        && self.label[b] == 2
+                        && delta.is_none_or(|(_, best)| self.dual[b] < best) {
+                        delta = Some((4, self.dual[b]));
+                        delta_blossom = Some(b);
+                    }
+                }
+                
+                let (delta_type, delta) = delta.unwrap_or_else(|| {
+                    (1, (*self.dual[..n].iter().min().unwrap()).max(0))
+                });
+                
+                for v in 0..n {
+                    match self.label[self.in_blossom[v]] {
+                        1 => self.dual[v] -= delta,
+                        2 => self.dual[v] += delta,
+                        _ => {}
+                    }
+                }
+                for b in n..2 * n {
+                    if self.blossom_base[b].is_some() && self.blossom_parent[b].is_none() {
+                        match self.label[b] {
+                            1 => self.dual[b] += delta,
+                            2 => self.dual[b] -= delta,
+                            _ => {}
+                        }
+                    }
+                }
+                
+                match delta_type {
+                    1 => break,
+                    2 => {
+                        let e = delta_edge.unwrap();
+                        self.allowed[e] = true;
+                        let (mut i, j, _) = self.edges[e];
+                        if self.label[self.in_blossom[i]] == 0 {
+                            i = j;
+                        }
+                        self.queue.push(i);
+                    }
+                    3 => {
+                        let e = delta_edge.unwrap();
+                        self.allowed[e] = true;
+                        self.queue.push(self.edges[e].0);
+                    }
+                    _ => self.expand_blossom(delta_blossom.unwrap(), false),
+                }
+            }
+            
+            if !augmented {
+                break;
+            }
+            
+            // End of stage: expand S-blossoms whose dual dropped to zero
+            for b in n..2 * n {
+                if self.blossom_parent[b].is_none()
+                    && self.blossom_base[b].is_some()
+                    && self.label[b] == 1
+                    && self.dual[b] == 0 {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+        
+        self.mate.iter().map(|m| m.map(|p| self.endpoint[p])).collect()
+    }
+    
+    fn slack(&self, k: usize) -> i64 {
+        let (i, j, w) = self.edges[k];
+        self.dual[i] + self.dual[j] - 2 * w
+    }
+    
+    fn blossom_leaves(&self, b: usize) -> Vec<usize> {
+        if b < self.n {
+            return vec![b];
+        }
+        self.blossom_children[b].iter().flat_map(|&t| self.blossom_leaves(t)).collect()
+    }
+    
+    /// Label the top blossom containing `w` with `t`, reached through endpoint `p`
+    fn assign_label(&mut self, w: usize, t: u8, p: Option<usize>) {
+        let b = self.in_blossom[w];
+        self.label[w] = t;
+        self.label[b] = t;
+        self.label_end[w] = p;
+        self.label_end[b] = p;
+        self.best_edge[w] = None;
+        self.best_edge[b] = None;
+        
+        if t == 1 {
+            let leaves = self.blossom_leaves(b);
+            self.queue.extend(leaves);
+        } else {
+            let base = self.blossom_base[b].unwrap();
+            let mate = self.mate[base].unwrap();
+            self.assign_label(self.endpoint[mate], 1, Some(mate ^ 1));
+        }
+    }
+    
+    /// Trace back from S-vertices v and w; returns the base of a new blossom, or None
+    /// when the paths reach two different roots (augmenting path)
+    fn scan_blossom(&mut self, v: usize, w: usize) -> Option<usize> {
+        let mut path = Vec::new();
+        let mut base = None;
+        let (mut v, mut w) = (Some(v), Some(w));
+        
+        // Alternate between the two paths; v is None only once both are exhausted
+        while let Some(node) = v {
+            let b = self.in_blossom[node];
+            if self.label[b] & 4 != 0 {
+                base = self.blossom_base[b];
+                break;
+            }
+            path.push(b);
+            self.label[b] = 5;
+            
+            v = self.label_end[b].map(|p| {
+                let t = self.in_blossom[self.endpoint[p]];
+                self.endpoint[self.label_end[t].unwrap()]
+            });
+            if w.is_some() {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+        
+        for b in path {
+            self.label[b] = 1;
+        }
+        base
+    }
+    
+    /// Create a blossom from the odd cycle closed by edge k, with the given base vertex
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (v, w, _) = self.edges[k];
+        let bb = self.in_blossom[base];
+        let mut bv = self.in_blossom[v];
+        let mut bw = self.in_blossom[w];
+        
+        let b = self.unused_blossoms.pop().unwrap();
+        self.formed += 1;
+        self.blossom_base[b] = Some(base);
+        self.blossom_parent[b] = None;
+        self.blossom_parent[bb] = Some(b);
+        
+        let mut path = Vec::new();
+        let mut endpoints = Vec::new();
+        while bv != bb {
+            self.blossom_parent[bv] = Some(b);
+            path.push(bv);
+            let p = self.label_end[bv].unwrap();
+            endpoints.push(p);
+            bv = self.in_blossom[self.endpoint[p]];
+        }
+        path.push(bb);
+        path.reverse();
+        endpoints.reverse();
+        endpoints.push(2 * k);
+        while bw != bb {
+            self.blossom_parent[bw] = Some(b);
+            path.push(bw);
+            let p = self.label_end[bw].unwrap();
+            endpoints.push(p ^ 1);
+            bw = self.in_blossom[self.endpoint[p]];
+        }
+        
+        self.label[b] = 1;
+        self.label_end[b] = self.label_end[bb];
+        self.dual[b] = 0;
+        
+        self.blossom_children[b] = path.clone();
+        self.blossom_endpoints[b] = endpoints;
+        
+        for leaf in self.blossom_leaves(b) {
+            if self.label[self.in_blossom[leaf]] == 2 {
+                self.queue.push(leaf);
+            }
+            self.in_blossom[leaf] = b;
+        }
+        
+        // Least-slack edge from the new blossom to every other S-blossom
+        let mut best_edge_to: Vec<Option<usize>> = vec![None; 2 * self.n];
+        for &child in &path {
+            let lists: Vec<Vec<usize>> = match self.blossom_best_edges[child].take() {
+                Some(list) => vec![list],
+                None => self.blossom_leaves(child)
+                    .into_iter()
+                    .map(|leaf| self.neighbour_ends[leaf].iter().map(|p| p / 2).collect())
+                    .collect(),
+            };
+            
+            for list in lists {
+                for e in list {
+                    let (mut i, mut j, _) = self.edges[e];
+                    if self.in_blossom[j] == b {
+                        std::mem::swap(&mut i, &mut j);
+                    }
+                    let bj = self.in_blossom[j];
+                    if bj != b
+                        && self.label[bj] == 1
+                        && best_edge_to[bj].is_none_or(|best| self.slack(e) < self.slack(best)) {
+                        best_edge_to[bj] = Some(e);
+                    }
+                }
+            }
+            self.best_edge[child] = None;
+        }
+        
+        let best_edges: Vec<usize> = best_edge_to.into_iter().flatten().collect();
+        self.best_edge[b] = best_edges.iter().copied().min_by_key(|&e| self.slack(e));
+        self.blossom_best_edges[b] = Some(best_edges);
+    }
+    
+    /// Dissolve blossom b; mid-stage T-blossoms relabel the children along the even path
+    fn expand_blossom(&mut self, b: usize, end_stage: bool) {
+        for s in self.blossom_children[b].clone() {
+            self.blossom_parent[s] = None;
+            if s < self.n {
+                self.in_blossom[s] = s;
+            } else if end_stage && self.dual[s] == 0 {
+                self.expand_blossom(s, end_stage);
+            } else {
+                for leaf in self.blossom_leaves(s) {
+                    self.in_blossom[leaf] = s;
+                }
+            }
+        }
+        
+        if !end_stage && self.label[b] == 2 {
+            let children = self.blossom_children[b].clone();
+            let endpoints = self.blossom_endpoints[b].clone();
+            let len = children.len() as isize;
+            let child = |j: isize| children[j.rem_euclid(len) as usize];
+            let endpoint_at = |j: isize| endpoints[j.rem_euclid(len) as usize];
+            
+            let entry = self.in_blossom[self.endpoint[self.label_end[b].unwrap() ^ 1]];
+            let mut j = children.iter().position(|&c| c == entry).unwrap() as isize;
+            let (j_step, endpoint_trick) = if j & 1 == 1 {
+                j -= len;
+                (1, 0)
+            } else {
+                (-1, 1)
+            };
+            
+            let mut p = self.label_end[b].unwrap();
+            while j != 0 {
+                self.label[self.endpoint[p ^ 1]] = 0;
+                let q = endpoint_at(j - endpoint_trick as isize) ^ endpoint_trick ^ 1;
+                self.label[self.endpoint[q]] = 0;
+                self.assign_label(self.endpoint[p ^ 1], 2, Some(p));
+                self.allowed[endpoint_at(j - endpoint_trick as isize) / 2] = true;
+                j += j_step;
+                p = endpoint_at(j - endpoint_trick as isize) ^ endpoint_trick;
+                self.allowed[p / 2] = true;
+                j += j_step;
+            }
+            
+            let bv = child(j);
+            self.label[self.endpoint[p ^ 1]] = 2;
+            self.label[bv] = 2;
+            self.label_end[self.endpoint[p ^ 1]] = Some(p);
+            self.label_end[bv] = Some(p);
+            self.best_edge[bv] = None;
+            j += j_step;
+            
+            while child(j) != entry {
+                let bv = child(j);
+                if self.label[bv] == 1 {
+                    j += j_step;
+                    continue;
+                }
+                
+                if let Some(v) = self.blossom_leaves(bv).into_iter().find(|&v| self.label[v] != 0) {
+                    self.label[v] = 0;
+                    let base_mate = self.mate[self.blossom_base[bv].unwrap()].unwrap();
+                    self.label[self.endpoint[base_mate]] = 0;
+                    self.assign_label(v, 2, self.label_end[v]);
+                }
+                j += j_step;
+            }
+        }
+        
+        self.label[b] = 0;
+        self.label_end[b] = None;
+        self.blossom_children[b].clear();
+        self.blossom_endpoints[b].clear();
+        self.blossom_base[b] = None;
+        self.blossom_best_edges[b] = None;
+        self.best_edge[b] = None;
+        self.unused_blossoms.push(b);
+        self.expanded += 1;
+    }
+    
+    /// Flip the matched edges inside blossom b so that vertex v becomes its base
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossom_parent[t] != Some(b) {
+            t = self.blossom_parent[t].unwrap();
+        }
+        if t >= self.n {
+            self.augment_blossom(t, v);
+        }
+        
+        let len = self.blossom_children[b].len() as isize;
+        let i = self.blossom_children[b].iter().position(|&c| c == t).unwrap();
+        let mut j = i as isize;
+        let (j_step, endpoint_trick) = if j & 1 == 1 {
+            j -= len;
+            (1, 0)
+        } else {
+            (-1, 1)
+        };
+        
+        while j != 0 {
+            j += j_step;
+            let t = self.blossom_children[b][j.rem_euclid(len) as usize];
+            let p = self.blossom_endpoints[b][(j - endpoint_trick as isize).rem_euclid(len) as usize] ^ endpoint_trick;
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p]);
+            }
+            j += j_step;
+            let t = self.blossom_children[b][j.rem_euclid(len) as usize];
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p ^ 1]);
+            }
+            self.mate[self.endpoint[p]] = Some(p ^ 1);
+            self.mate[self.endpoint[p ^ 1]] = Some(p);
+        }
+        
+        self.blossom_children[b].rotate_left(i);
+        self.blossom_endpoints[b].rotate_left(i);
+        self.blossom_base[b] = self.blossom_base[self.blossom_children[b][0]];
+    }
+    
+    /// Augment along the path through edge k between two S-vertices of different trees
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+        for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+            loop {
+                let bs = self.in_blossom[s];
+                if bs >= self.n {
+                    self.augment_blossom(bs, s);
+                }
+                self.mate[s] = Some(p);
+                
+                let Some(end) = self.label_end[bs] else { break };
+                let t = self.endpoint[end];
+                let bt = self.in_blossom[t];
+                let bt_end = self.label_end[bt].unwrap();
+                s = self.endpoint[bt_end];
+                let j = self.endpoint[bt_end ^ 1];
+                if bt >= self.n {
+                    self.augment_blossom(bt, j);
+                }
+                self.mate[j] = Some(bt_end);
+                p = bt_end ^ 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::polygons_matrix;
+    
+    /// Cheapest perfect matching by trying every pairing
+    fn brute_force(cost: &[Vec<f64>], free: &mut Vec<usize>) -> f64 {
+        if free.is_empty() {
+            return 0.0;
+        }
+        let first = free.remove(0);
+        let mut best = f64::INFINITY;
+        for k in 0..free.len() {
+            let partner = free.remove(k);
+            best = best.min(cost[first][partner] + brute_force(cost, free));
+            free.insert(k, partner);
+        }
+        free.insert(0, first);
+        best
+    }
+    
+    #[test]
+    fn test_matches_brute_force() {
+        for seed in 0..40usize {
+            let n = 2 + 2 * (seed % 5);
+            let cost: Vec<Vec<f64>> = (0..n)
+                .map(|i| (0..n)
+                    .map(|j| {
+                        let (a, b) = (i.min(j), i.max(j));
+                        if i == j { 0.0 } else { ((a * 31 + b * 17 + seed * 7) % 23 + 1) as f64 }
+                    })
+                    .collect())
+                .collect();
+            
+            let mate = min_cost_perfect_matching(&cost);
+            for (v, &m) in mate.iter().enumerate() {
+                assert_ne!(v, m);
+                assert_eq!(mate[m], v);
+            }
+            
+            let total: f64 = (0..n).filter(|&v| v < mate[v]).map(|v| cost[v][mate[v]]).sum();
+            assert!((total - brute_force(&cost, &mut (0..n).collect())).abs() < 1e-6, "n = {}, seed = {}", n, seed);
+        }
+    }
+    
+    #[test]
+    fn test_odd_cycles_need_blossoms() {
+        for sizes in [&[3, 3][..], &[5, 5], &[3, 3, 3, 5], &[3, 3, 3, 3, 3, 1]] {
+            let cost = polygons_matrix(sizes);
+            let n = cost.len();
+            
+            let mut matching = Matching::min_cost(&cost);
+            let mate: Vec<usize> = matching.solve(true).into_iter().map(Option::unwrap).collect();
+            assert!(matching.formed > 0 && matching.expanded > 0, "{:?}", sizes);
+            
+            let total: f64 = (0..n).filter(|&v| v < mate[v]).map(|v| cost[v][mate[v]]).sum();
+            assert!((total - brute_force(&cost, &mut (0..n).collect())).abs() < 1e-6, "{:?}", sizes);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::Result;
+
+use crate::local_search::LocalSearch;
+use crate::tsp_solver::{tour_cost, SubsetTours};
+use crate::TSPError;
+
+/// Largest number of customers (cities other than the depot) for the exact subset DP
+const MAX_EXACT_CUSTOMERS: usize = 16;
+
+/// Rounds of relocation and per-route local search when balancing route lengths
+const BALANCE_ROUNDS: usize = 100;
+
+/// What the salesmen minimise together
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    /// Sum of all route lengths
+    TotalDistance,
+    /// Length of the longest route (min-max)
+    LongestRoute,
+}
+
+/// Multiple travelling salesmen: `salesmen` closed routes from the depot that together
+/// visit every other city once; every salesman visits at least one city
+pub struct MultiSalesman {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    salesmen: usize,
+    depot: usize,
+    objective: Objective,
+}
+
+impl MultiSalesman {
+    pub fn new(distance_matrix: Vec<Vec<f64>>, salesmen: usize) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            salesmen,
+            depot: 0,
+            objective: Objective::TotalDistance,
+        }
+    }
+    
+    pub fn with_depot(mut self, depot: usize) -> Self {
+        self.depot = depot;
+        self
+    }
+    
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+    
+    fn validate(&self) -> Result<()> {
+        if self.salesmen == 0 || self.salesmen >= self.n {
+            return Err(TSPError::GraphValidation(
+                format!("{} salesmen need between 1 and {} customers each to visit", self.salesmen, self.n - 1)
+            ).into());
+        }
+        Ok(())
+    }
+    
+    /// Objective value of a set of routes
+    pub fn score(&self, routes: &[Vec<usize>]) -> f64 {
+        let lengths = routes.iter().map(|r| tour_cost(&self.distance_matrix, r));
+        match self.objective {
+            Objective::TotalDistance => lengths.sum(),
+            Objective::LongestRoute => lengths.fold(0.0, f64::max),
+        }
+    }
+    
+    /// Exact DP: the best closed route through every subset of customers (Held-Karp),
+    /// then the best partition of all customers into `salesmen` such subsets
+    pub fn solve_exact(&self, verbose: bool) -> Result<(f64, Vec<Vec<usize>>)> {
+        self.validate()?;
+        
+        let customers: Vec<usize> = (0..self.n).filter(|&c| c != self.depot).collect();
+        let m = customers.len();
+        if m > MAX_EXACT_CUSTOMERS {
+            return Err(TSPError::GraphValidation(
+                format!("Maximum {} customers for the exact multi-salesman DP", MAX_EXACT_CUSTOMERS)
+            ).into());
+        }
+        
+        println!("  • Route DP over {} customer subsets", 1usize << m);
+        let subsets = SubsetTours::new(&self.distance_matrix, self.depot, customers);
+        let route_cost = &subsets.cost;
+        
+        // best[j][mask] = (objective, tie-break) for j + 1 salesmen covering mask
+        let full = (1usize << m) - 1;
+        let mut best = vec![vec![(f64::INFINITY, f64::INFINITY); full + 1]; self.salesmen];
+        let mut choice = vec![vec![0usize; full + 1]; self.salesmen];
+        for mask in 1..=full {
+            best[0][mask] = (route_cost[mask], route_cost[mask]);
+            choice[0][mask] = mask;
+        }
+        
+        for j in 1..self.salesmen {
+            for mask in 1..=full {
+                // The route holding the lowest customer of `mask`; avoids counting orders twice
+                let lowest = mask & mask.wrapping_neg();
+                let rest = mask ^ lowest;
+                let mut sub = rest;
+                loop {
+                    let route = sub | lowest;
+                    let others = mask ^ route;
+                    if others != 0 && best[j - 1][others].0.is_finite() {
+                        let value = self.combine(best[j - 1][others], route_cost[route]);
+                        if value < best[j][mask] {
+                            best[j][mask] = value;
+                            choice[j][mask] = route;
+                        }
+                    }
+                    if sub == 0 {
+                        break;
+                    }
+                    sub = (sub - 1) & rest;
+                }
+            }
+            if verbose {
+                println!("    • {} salesmen: best value {:.1}", j + 1, best[j][full].0);
+            }
+        }
+        
+        // Unpack the partition, then each route from the subset DP
+        let mut routes = Vec::with_capacity(self.salesmen);
+        let mut mask = full;
+        for j in (0..self.salesmen).rev() {
+            let subset = choice[j][mask];
+            routes.push(subsets.tour(subset));
+            mask ^= subset;
+        }
+        
+        Ok((self.score(&routes), routes))
+    }
+    
+    /// Objective (and tie-break) after adding one route to a partial partition
+    fn combine(&self, (value, total): (f64, f64), route: f64) -> (f64, f64) {
+        match self.objective {
+            Objective::TotalDistance => (value + route, total + route),
+            Objective::LongestRoute => (value.max(route), total + route),
+        }
+    }
+    
+    /// Single-tour instance for the ordinary TSP algorithms: the depot plus `salesmen - 1`
+    /// copies of it (appended after the original cities). Depot copies are `penalty` apart,
+    /// so a tour only places them next to each other when that cannot be avoided.
+    pub fn depot_copies(&self) -> Result<Vec<Vec<f64>>> {
+        self.validate()?;
+        
+        let copies = self.salesmen - 1;
+        let longest = self.distance_matrix.iter().flatten().cloned().fold(0.0, f64::max);
+        let penalty = self.n as f64 * longest + 1.0;
+        let original = |c: usize| if c >= self.n { self.depot } else { c };
+        let is_depot = |c: usize| c >= self.n || c == self.depot;
+        
+        Ok((0..self.n + copies)
+            .map(|i| (0..self.n + copies)
+                .map(|j| match (i == j, is_depot(i) && is_depot(j)) {
+                    (true, _) => 0.0,
+                    (false, true) => penalty,
+                    (false, false) => self.distance_matrix[original(i)][original(j)],
+                })
+                .collect())
+            .collect())
+    }
+    
+    /// Cut a tour of the `depot_copies` instance into routes, splitting the busiest route
+    /// if two depots ended up adjacent, then balance for the longest-route objective
+    pub fn split_tour(&self, tour: &[usize]) -> Vec<Vec<usize>> {
+        let is_depot = |c: usize| c >= self.n || c == self.depot;
+        let begin = tour.iter().position(|&c| c == self.depot).unwrap_or(0);
+        
+        let mut routes: Vec<Vec<usize>> = Vec::new();
+        for &c in tour[begin..].iter().chain(&tour[..begin]) {
+            if is_depot(c) {
+                routes.push(vec![self.depot]);
+            } else if let Some(route) = routes.last_mut() {
+                route.push(c);
+            }
+        }
+        routes.retain(|r| r.len() > 1);
+        
+        while routes.len() < self.salesmen {
+            let busiest = (0..routes.len()).max_by_key(|&r| routes[r].len()).unwrap();
+            let middle = routes[busiest].len().div_ceil(2);
+            let tail: Vec<usize> = routes[busiest].split_off(middle);
+            routes.push(std::iter::once(self.depot).chain(tail).collect());
+        }
+        
+        if self.objective == Objective::LongestRoute {
+            self.balance(&mut routes);
+        }
+        routes
+    }
+    
+    /// Min-max improvement: move customers off the longest route while that shortens it
+    /// without making the receiving route the new longest, and re-optimise routes with
+    /// local search in between
+    fn balance(&self, routes: &mut [Vec<usize>]) {
+        let d = &self.distance_matrix;
+        let length = |r: &Vec<usize>| tour_cost(d, r);
+        
+        for _ in 0..BALANCE_ROUNDS {
+            for route in routes.iter_mut() {
+                self.optimise_route(route);
+            }
+            
+            let longest = (0..routes.len())
+                .max_by(|&a, &b| length(&routes[a]).total_cmp(&length(&routes[b])))
+                .unwrap();
+            let limit = length(&routes[longest]);
+            
+            // Best single move: (new longest of the pair, customer position, target route, position)
+            let mut best: Option<(f64, usize, usize, usize)> = None;
+            if routes[longest].len() > 2 {
+                for from in 1..routes[longest].len() {
+                    let mut shorter = routes[longest].clone();
+                    let city = shorter.remove(from);
+                    let shorter_length = length(&shorter);
+                    
+                    for (target, route) in routes.iter().enumerate() {
+                        if target == longest {
+                            continue;
+                        }
+                        for pos in 1..=route.len() {
+                            let next = route.get(pos).copied().unwrap_or(route[0]);
+                            let grown = length(route) + d[route[pos - 1]][city] + d[city][next] - d[route[pos - 1]][next];
+                            let worst = grown.max(shorter_length);
+                            if worst < limit - 1e-9 && best.is_none_or(|(w, ..)| worst < w) {
+                                best = Some((worst, from, target, pos));
+                            }
+                        }
+                    }
+                }
+            }
+            
+            match best {
+                Some((_, from, target, pos)) => {
+                    let city = routes[longest].remove(from);
+                    routes[target].insert(pos, city);
+                }
+                None => break,
+            }
+        }
+    }
+    
+    /// Re-order one route (depot first) with 2-opt/Or-opt local search
+    fn optimise_route(&self, route: &mut Vec<usize>) {
+        let matrix: Vec<Vec<f64>> = route.iter()
+            .map(|&a| route.iter().map(|&b| self.distance_matrix[a][b]).collect())
+            .collect();
+        let mut order: Vec<usize> = (0..route.len()).collect();
+        LocalSearch::new(matrix).improve_quietly(&mut order);
+        
+        let depot = order.iter().position(|&c| c == 0).unwrap_or(0);
+        order.rotate_left(depot);
+        *route = order.into_iter().map(|i| route[i]).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::points_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_exact_matches_depot_copies() {
+        // Total distance: the exact DP agrees with Held-Karp on the depot-copy instance
+        let matrix = points_matrix(9);
+        let mtsp = MultiSalesman::new(matrix.clone(), 3).with_depot(4);
+        let (exact, routes) = mtsp.solve_exact(false).unwrap();
+        
+        let (tour_length, tour) = TSPSolver::new(mtsp.depot_copies().unwrap()).solve(false).unwrap();
+        let split = mtsp.split_tour(&tour);
+        
+        assert!((exact - tour_length).abs() < 1e-9);
+        assert!((mtsp.score(&split) - exact).abs() < 1e-9);
+        assert_eq!(routes.len(), 3);
+        for route in routes.iter().chain(&split) {
+            assert_eq!(route[0], 4);
+            assert!(route.len() > 1);
+        }
+    }
+    
+    #[test]
+    fn test_min_max_balances_routes() {
+        let matrix = points_matrix(11);
+        let total = MultiSalesman::new(matrix.clone(), 2);
+        let longest = MultiSalesman::new(matrix.clone(), 2).with_objective(Objective::LongestRoute);
+        
+        let (_, total_routes) = total.solve_exact(false).unwrap();
+        let (min_max, routes) = longest.solve_exact(false).unwrap();
+        
+        // The min-max optimum never has a longer worst route, and pays for it in total distance
+        assert!(min_max <= longest.score(&total_routes) + 1e-9);
+        assert!(total.score(&routes) >= total.score(&total_routes) - 1e-9);
+        
+        let mut visited: Vec<usize> = routes.iter().flat_map(|r| r[1..].to_vec()).collect();
+        visited.sort();
+        assert_eq!(visited, (1..11).collect::<Vec<_>>());
+    }
+}
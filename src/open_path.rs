@@ -0,0 +1,154 @@
+use crate::tsp_solver::{is_symmetric, Route};
+
+/// Open path expressed as a closed tour through one extra city, so that every tour
+/// solver can optimise it. The extra city is at distance 0 from the allowed endpoints
+/// and `penalty` from the rest; `penalty` exceeds what moving a misplaced endpoint
+/// can cost, so optimal tours only connect it to allowed endpoints.
+pub struct PathAsTour {
+    /// Original matrix plus the extra city as the last row and column
+    pub matrix: Vec<Vec<f64>>,
+    start: usize,
+    route: Route,
+    n: usize,
+    symmetric: bool,
+    penalty: f64,
+}
+
+impl PathAsTour {
+    pub fn new(distance_matrix: &[Vec<f64>], start: usize, route: Route) -> Self {
+        let n = distance_matrix.len();
+        let symmetric = is_symmetric(distance_matrix);
+        let longest = distance_matrix.iter().flatten().cloned().fold(0.0, f64::max);
+        let penalty = 2.0 * longest + 1.0;
+        
+        let (first, last) = match route {
+            Route::Path { end } => (Some(start), end),
+            _ => (None, None),
+        };
+        // Cost of entering the path at `c` (extra city -> c) and leaving it at `c` (c -> extra city)
+        let enter = |c: usize| if first.is_none_or(|s| s == c) { 0.0 } else { penalty };
+        let leave = |c: usize| if last.is_none_or(|e| e == c) { 0.0 } else { penalty };
+        
+        let mut matrix: Vec<Vec<f64>> = distance_matrix.to_vec();
+        let mut extra_row = Vec::with_capacity(n + 1);
+        for (c, row) in matrix.iter_mut().enumerate() {
+            if symmetric {
+                // Either endpoint may touch the extra city; the path is oriented afterwards
+                let link = if first == Some(c) || last == Some(c) || first.is_none() {
+                    0.0
+                } else {
+                    penalty
+                };
+                row.push(link);
+                extra_row.push(link);
+            } else {
+                row.push(leave(c));
+                extra_row.push(enter(c));
+            }
+        }
+        extra_row.push(0.0);
+        matrix.push(extra_row);
+        
+        Self {
+            matrix,
+            start,
+            route,
+            n,
+            symmetric,
+            penalty,
+        }
+    }
+    
+    /// Amount the extra city adds to every feasible tour
+    pub fn offset(&self) -> f64 {
+        match self.route {
+            // Symmetric path with a free end: one link always pays the penalty
+            Route::Path { end: None } if self.symmetric => self.penalty,
+            _ => 0.0,
+        }
+    }
+    
+    /// Cut a tour through the extra city into a path over the original cities, moving
+    /// the start (and end) into place if a heuristic left them elsewhere
+    pub fn to_path(&self, tour: &[usize]) -> Vec<usize> {
+        let cut = tour.iter().position(|&c| c == self.n).unwrap_or(0);
+        let mut path: Vec<usize> = tour[cut..].iter()
+            .chain(&tour[..cut])
+            .copied()
+            .filter(|&c| c != self.n)
+            .collect();
+        
+        if let Route::Path { end } = self.route {
+            let start = self.start;
+            if self.symmetric && path.first() != Some(&start) && (path.last() == Some(&start) || end == Some(path[0])) {
+                path.reverse();
+            }
+            move_to(&mut path, start, 0);
+            if let Some(end) = end {
+                let last = path.len() - 1;
+                move_to(&mut path, end, last);
+            }
+        }
+        
+        path
+    }
+    
+    /// Close a path into a tour through the extra city
+    pub fn to_tour(&self, path: &[usize]) -> Vec<usize> {
+        let mut tour = path.to_vec();
+        tour.push(self.n);
+        tour
+    }
+}
+
+/// Move `city` to index `target` of `path`, keeping the order of the others
+fn move_to(path: &mut Vec<usize>, city: usize, target: usize) {
+    if let Some(from) = path.iter().position(|&c| c == city) {
+        if from != target {
+            path.remove(from);
+            path.insert(target, city);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pseudo_random_matrix;
+    use crate::heuristics::{Construction, ConstructionHeuristic};
+    use crate::tsp_solver::{path_cost, tour_cost, TSPSolver};
+    
+    #[test]
+    fn test_optimal_tour_gives_optimal_path() {
+        for symmetric in [true, false] {
+            let matrix = pseudo_random_matrix(8, symmetric);
+            
+            for route in [Route::Path { end: None }, Route::Path { end: Some(5) }, Route::FreePath] {
+                let (expected, _) = TSPSolver::new(matrix.clone()).with_start(2).with_route(route).solve(false).unwrap();
+                
+                let instance = PathAsTour::new(&matrix, 2, route);
+                let (tour_length, tour) = TSPSolver::new(instance.matrix.clone()).solve(false).unwrap();
+                let path = instance.to_path(&tour);
+                
+                assert!((tour_length - instance.offset() - expected).abs() < 1e-9, "{:?}", route);
+                assert!((path_cost(&matrix, &path) - expected).abs() < 1e-9, "{:?}", route);
+                assert!((tour_cost(&instance.matrix, &instance.to_tour(&path)) - tour_length).abs() < 1e-9);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_heuristic_tour_is_repaired() {
+        let matrix = pseudo_random_matrix(12, false);
+        let route = Route::Path { end: Some(7) };
+        let instance = PathAsTour::new(&matrix, 0, route);
+        
+        let tour = ConstructionHeuristic::new(instance.matrix.clone(), Construction::NearestNeighbour)
+            .nearest_neighbour_from(3);
+        let path = instance.to_path(&tour);
+        
+        assert_eq!(path.len(), 12);
+        assert_eq!(path[0], 0);
+        assert_eq!(path[11], 7);
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::Result;
+
+use crate::local_search::LocalSearch;
+use crate::tsp_solver::{tour_cost, SubsetTours};
+use crate::TSPError;
+
+/// Largest number of cities for the exact subset DP; larger inputs use greedy insertion
+const MAX_EXACT_CITIES: usize = 20;
+
+/// Insert/optimise/drop rounds of the heuristic
+const MAX_ROUNDS: usize = 50;
+
+/// Which cities are worth visiting
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrizeObjective {
+    /// Collect as much prize as possible with a tour no longer than `budget`
+    Orienteering { budget: f64 },
+    /// Minimise tour length plus the prizes (as penalties) of the skipped cities
+    Penalties,
+}
+
+/// Tour from the start city through a chosen subset of the cities
+pub struct PrizeCollecting {
+    distance_matrix: Vec<Vec<f64>>,
+    prizes: Vec<f64>,
+    n: usize,
+    start: usize,
+    objective: PrizeObjective,
+}
+
+impl PrizeCollecting {
+    pub fn new(distance_matrix: Vec<Vec<f64>>, prizes: Vec<f64>) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            prizes,
+            n,
+            start: 0,
+            objective: PrizeObjective::Penalties,
+        }
+    }
+    
+    pub fn with_start(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+    
+    pub fn with_objective(mut self, objective: PrizeObjective) -> Self {
+        self.objective = objective;
+        self
+    }
+    
+    /// Prize collected by a tour (the start city included)
+    pub fn prize(&self, tour: &[usize]) -> f64 {
+        tour.iter().map(|&c| self.prizes[c]).sum()
+    }
+    
+    /// Penalty paid for the cities a tour skips
+    pub fn penalty(&self, tour: &[usize]) -> f64 {
+        self.prizes.iter().sum::<f64>() - self.prize(tour)
+    }
+    
+    /// Returns (tour length, tour from the start city); exact for up to `MAX_EXACT_CITIES`
+    pub fn solve(&self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        self.validate()?;
+        
+        let tour = if self.n <= MAX_EXACT_CITIES {
+            self.solve_exact(verbose)
+        } else {
+            self.insertion_tour(verbose)
+        };
+        Ok((tour_cost(&self.distance_matrix, &tour), tour))
+    }
+    
+    /// Like `solve`, but always by greedy insertion
+    pub fn solve_heuristic(&self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        self.validate()?;
+        
+        let tour = self.insertion_tour(verbose);
+        Ok((tour_cost(&self.distance_matrix, &tour), tour))
+    }
+    
+    fn validate(&self) -> Result<()> {
+        if let PrizeObjective::Orienteering { budget } = self.objective {
+            if budget < 0.0 {
+                return Err(TSPError::GraphValidation("The travel budget must not be negative".to_string()).into());
+            }
+        }
+        Ok(())
+    }
+    
+    /// Subset DP: the shortest tour through every subset, then the best subset
+    fn solve_exact(&self, verbose: bool) -> Vec<usize> {
+        let others: Vec<usize> = (0..self.n).filter(|&c| c != self.start).collect();
+        println!("  • Subset DP over {} subsets of {} cities", 1usize << others.len(), others.len());
+        let subsets = SubsetTours::new(&self.distance_matrix, self.start, others.clone());
+        
+        let mut prize = vec![self.prizes[self.start]; subsets.cost.len()];
+        for mask in 1..prize.len() {
+            let lowest = mask.trailing_zeros() as usize;
+            prize[mask] = prize[mask & (mask - 1)] + self.prizes[others[lowest]];
+        }
+        
+        let total: f64 = self.prizes.iter().sum();
+        let key = |mask: usize| match self.objective {
+            // Most prize within the budget, then the shortest tour
+            PrizeObjective::Orienteering { budget } if subsets.cost[mask] <= budget + 1e-9 => {
+                (-prize[mask], subsets.cost[mask])
+            }
+            PrizeObjective::Orienteering { .. } => (f64::INFINITY, f64::INFINITY),
+            PrizeObjective::Penalties => (subsets.cost[mask] + total - prize[mask], subsets.cost[mask]),
+        };
+        
+        let best = (0..subsets.cost.len())
+            .min_by(|&a, &b| {
+                let ((score_a, cost_a), (score_b, cost_b)) = (key(a), key(b));
+                score_a.total_cmp(&score_b).then(cost_a.total_cmp(&cost_b))
+            })
+            .unwrap_or(0);
+        if verbose {
+            println!("    • Best subset visits {} of {} cities", best.count_ones() + 1, self.n);
+        }
+        subsets.tour(best)
+    }
+    
+    /// Greedy insertion (best prize per added distance for orienteering, best penalty minus
+    /// added distance otherwise), local search on the visited cities, and for penalties
+    /// dropping cities that cost more to reach than they are worth, until nothing changes
+    fn insertion_tour(&self, verbose: bool) -> Vec<usize> {
+        println!("  • Greedy insertion with local search for {} cities", self.n);
+        let mut tour = vec![self.start];
+        
+        for round in 0..MAX_ROUNDS {
+            let before = tour.clone();
+            self.insert_greedily(&mut tour);
+            self.optimise(&mut tour);
+            if self.objective == PrizeObjective::Penalties {
+                self.drop_unprofitable(&mut tour);
+            }
+            
+            if verbose {
+                println!("    • Round {}: {} cities, length {:.1}, prize {:.1}",
+                        round + 1, tour.len(), tour_cost(&self.distance_matrix, &tour), self.prize(&tour));
+            }
+            if tour == before {
+                break;
+            }
+        }
+        
+        tour
+    }
+    
+    /// Insert unvisited cities one at a time while one is worth it. The cheapest position
+    /// of every city is cached and only rescanned when its edge is replaced.
+    fn insert_greedily(&self, tour: &mut Vec<usize>) {
+        let d = &self.distance_matrix;
+        let mut visited = vec![false; self.n];
+        for &c in tour.iter() {
+            visited[c] = true;
+        }
+        let mut length = tour_cost(d, tour);
+        
+        // (added distance, predecessor) of the cheapest insertion per city
+        let cheapest = |tour: &[usize], city: usize| {
+            (0..tour.len())
+                .map(|i| {
+                    let (a, b) = (tour[i], tour[(i + 1) % tour.len()]);
+                    (d[a][city] + d[city][b] - d[a][b], a)
+                })
+                .min_by(|x, y| x.0.total_cmp(&y.0))
+                .unwrap()
+        };
+        let mut best: Vec<(f64, usize)> = (0..self.n)
+            .map(|c| if visited[c] { (f64::INFINITY, 0) } else { cheapest(tour, c) })
+            .collect();
+        
+        loop {
+            let candidate = (0..self.n)
+                .filter(|&c| !visited[c] && self.prizes[c] > 0.0)
+                .filter_map(|c| {
+                    let added = best[c].0.max(0.0);
+                    let score = match self.objective {
+                        PrizeObjective::Orienteering { budget } if length + added <= budget + 1e-9 => {
+                            self.prizes[c] / (added + 1e-9)
+                        }
+                        PrizeObjective::Orienteering { .. } => return None,
+                        PrizeObjective::Penalties if self.prizes[c] > added => self.prizes[c] - added,
+                        PrizeObjective::Penalties => return None,
+                    };
+                    Some((score, c))
+                })
+                .max_by(|x, y| x.0.total_cmp(&y.0));
+            
+            let Some((_, city)) = candidate else {
+                break;
+            };
+            
+            let (added, after) = best[city];
+            let at = tour.iter().position(|&c| c == after).unwrap() + 1;
+            let before = tour[at % tour.len()];
+            tour.insert(at, city);
+            visited[city] = true;
+            length += added;
+            
+            // Edge (after, before) is gone; (after, city) and (city, before) are new
+            for c in (0..self.n).filter(|&c| !visited[c]) {
+                if best[c].1 == after {
+                    best[c] = cheapest(tour, c);
+                } else {
+                    let via_new = (d[city][c] + d[c][before] - d[city][before], city);
+                    let via_after = (d[after][c] + d[c][city] - d[after][city], after);
+                    for option in [via_new, via_after] {
+                        if option.0 < best[c].0 {
+                            best[c] = option;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Remove visited cities whose detour costs more than their penalty, worst first
+    fn drop_unprofitable(&self, tour: &mut Vec<usize>) {
+        let d = &self.distance_matrix;
+        loop {
+            let worst = (1..tour.len())
+                .map(|i| {
+                    let (a, c, b) = (tour[i - 1], tour[i], tour[(i + 1) % tour.len()]);
+                    (d[a][c] + d[c][b] - d[a][b] - self.prizes[c], i)
+                })
+                .filter(|&(gain, _)| gain > 1e-9)
+                .max_by(|x, y| x.0.total_cmp(&y.0));
+            
+            match worst {
+                Some((_, i)) => {
+                    tour.remove(i);
+                }
+                None => break,
+            }
+        }
+    }
+    
+    /// Re-order the visited cities (start first) with 2-opt/Or-opt local search
+    fn optimise(&self, tour: &mut Vec<usize>) {
+        if tour.len() < 4 {
+            return;
+        }
+        let matrix: Vec<Vec<f64>> = tour.iter()
+            .map(|&a| tour.iter().map(|&b| self.distance_matrix[a][b]).collect())
+            .collect();
+        let mut order: Vec<usize> = (0..tour.len()).collect();
+        LocalSearch::new(matrix).improve_quietly(&mut order);
+        
+        let start = order.iter().position(|&c| c == 0).unwrap_or(0);
+        order.rotate_left(start);
+        *tour = order.into_iter().map(|i| tour[i]).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::points_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_exact_against_brute_force() {
+        let n = 8;
+        let matrix = points_matrix(n);
+        let prizes: Vec<f64> = (0..n).map(|i| ((i * 13) % 7 * 10) as f64).collect();
+        let total: f64 = prizes.iter().sum();
+        
+        // Best tour over each subset containing the start (city 2), by brute force
+        let subset_tours: Vec<(f64, f64)> = (0..1usize << n)
+            .filter(|mask| mask & (1 << 2) != 0)
+            .map(|mask| {
+                let cities: Vec<usize> = (0..n).filter(|&c| mask & (1 << c) != 0).collect();
+                let sub: Vec<Vec<f64>> = cities.iter()
+                    .map(|&a| cities.iter().map(|&b| matrix[a][b]).collect())
+                    .collect();
+                let (length, _) = TSPSolver::new(sub).solve(false).unwrap();
+                (length, cities.iter().map(|&c| prizes[c]).sum())
+            })
+            .collect();
+        
+        let solver = PrizeCollecting::new(matrix.clone(), prizes.clone()).with_start(2);
+        let (length, tour) = solver.solve(false).unwrap();
+        let expected = subset_tours.iter().map(|(l, p)| l + total - p).fold(f64::INFINITY, f64::min);
+        assert!((length + solver.penalty(&tour) - expected).abs() < 1e-9);
+        assert_eq!(tour[0], 2);
+        
+        let budget = 150.0;
+        let solver = solver.with_objective(PrizeObjective::Orienteering { budget });
+        let (length, tour) = solver.solve(false).unwrap();
+        let expected = subset_tours.iter().filter(|(l, _)| *l <= budget).map(|(_, p)| *p).fold(0.0, f64::max);
+        assert!(length <= budget);
+        assert_eq!(solver.prize(&tour), expected);
+    }
+    
+    #[test]
+    fn test_heuristic_respects_budget() {
+        let n = 60;
+        let matrix = points_matrix(n);
+        let prizes = vec![1.0; n];
+        
+        let solver = PrizeCollecting::new(matrix.clone(), prizes.clone())
+            .with_objective(PrizeObjective::Orienteering { budget: 300.0 });
+        let (length, tour) = solver.solve(false).unwrap();
+        assert!(length <= 300.0 + 1e-9);
+        assert!(tour.len() > 5);
+        assert_eq!(tour[0], 0);
+        
+        // Every detour costs more than its penalty: the start alone is best
+        let solver = PrizeCollecting::new(matrix, vec![0.01; n]);
+        assert_eq!(solver.solve(false).unwrap(), (0.0, vec![0]));
+    }
+}
@@ -0,0 +1,218 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Redraw interval of the terminal progress bar
+const REDRAW_EVERY: Duration = Duration::from_millis(100);
+
+/// Width of the bar in characters
+const BAR_WIDTH: usize = 30;
+
+/// Snapshot of a running solver
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    /// States computed so far; search nodes, iterations or generations for other solvers
+    pub states: u64,
+    /// States of the whole search (0 when unknown)
+    pub total_states: u64,
+    /// Subset size being computed; the DP works down from `layers` to 1, and other
+    /// solvers have no layers (0)
+    pub layer: usize,
+    pub layers: usize,
+    /// Best route cost known so far (the DP knows a greedy route until it is done)
+    pub best_cost: Option<f64>,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Share of the states done, between 0 and 1
+    pub fn fraction(&self) -> f64 {
+        if self.total_states == 0 {
+            return 0.0;
+        }
+        (self.states as f64 / self.total_states as f64).min(1.0)
+    }
+    
+    /// Remaining time if the states keep coming at the rate so far
+    pub fn eta(&self) -> Option<Duration> {
+        let fraction = self.fraction();
+        (fraction > 0.0).then(|| self.elapsed.mul_f64((1.0 - fraction) / fraction))
+    }
+}
+
+/// Receives progress reports from a solver. Reports come from the solving thread, often
+/// thousands of times per second, so implementations should be cheap.
+pub trait ProgressObserver: Send + Sync {
+    fn update(&self, progress: &Progress);
+    
+    /// Final report, once the search is complete
+    fn finish(&self, progress: &Progress) {
+        self.update(progress);
+    }
+}
+
+/// Reports of a search that counts steps (nodes, iterations or generations) instead of
+/// DP layers; does nothing without an observer
+pub struct SearchProgress {
+    observer: Option<Arc<dyn ProgressObserver>>,
+    /// Step budget (0 when unknown)
+    total_steps: u64,
+    started: Instant,
+}
+
+impl SearchProgress {
+    pub fn new(observer: Option<Arc<dyn ProgressObserver>>, total_steps: u64) -> Self {
+        Self {
+            observer,
+            total_steps,
+            started: Instant::now(),
+        }
+    }
+    
+    /// Measure elapsed time from now, for a solver that is run again
+    pub fn restart(&mut self) {
+        self.started = Instant::now();
+    }
+    
+    pub fn update(&self, steps: u64, best_cost: f64) {
+        if let Some(observer) = &self.observer {
+            observer.update(&self.snapshot(steps, best_cost));
+        }
+    }
+    
+    /// Final report; the step budget counts as used up
+    pub fn finish(&self, steps: u64, best_cost: f64) {
+        if let Some(observer) = &self.observer {
+            observer.finish(&self.snapshot(steps.max(self.total_steps), best_cost));
+        }
+    }
+    
+    fn snapshot(&self, steps: u64, best_cost: f64) -> Progress {
+        Progress {
+            states: steps,
+            total_states: self.total_steps,
+            layer: 0,
+            layers: 0,
+            best_cost: best_cost.is_finite().then_some(best_cost),
+            elapsed: self.started.elapsed(),
+        }
+    }
+}
+
+/// Progress bar with ETA on stderr, redrawn at most every `interval` (`REDRAW_EVERY` by
+/// default); the first draw waits one interval after the first report, so quick searches
+/// leave no bar
+pub struct ProgressBar {
+    interval: Duration,
+    /// Time of the last draw, or of the first report before anything was drawn
+    last_draw: Mutex<Option<Instant>>,
+    drawn: AtomicBool,
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self::with_interval(REDRAW_EVERY)
+    }
+}
+
+impl ProgressBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_draw: Mutex::new(None),
+            drawn: AtomicBool::new(false),
+        }
+    }
+    
+    fn draw(&self, progress: &Progress) {
+        let filled = (progress.fraction() * BAR_WIDTH as f64).round() as usize;
+        let eta = progress.eta().map_or("?".to_string(), |eta| format!("{:.1}s", eta.as_secs_f64()));
+        let stage = match progress.layers {
+            0 => format!("{} steps", progress.states),
+            layers => format!("layer {:>2}/{}  {} states", progress.layer, layers, progress.states),
+        };
+        let best = progress.best_cost.map_or(String::new(), |cost| format!("  best {:.1}", cost));
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(stderr, "\r  [{}{}] {:>5.1}%  {}{}  {:.1}s elapsed, ETA {}   ",
+                       "#".repeat(filled), "-".repeat(BAR_WIDTH - filled), progress.fraction() * 100.0,
+                       stage, best, progress.elapsed.as_secs_f64(), eta);
+        let _ = stderr.flush();
+    }
+}
+
+impl ProgressObserver for ProgressBar {
+    fn update(&self, progress: &Progress) {
+        let mut last_draw = self.last_draw.lock().unwrap();
+        match *last_draw {
+            Some(at) if at.elapsed() < self.interval => return,
+            Some(_) => {}
+            None => {
+                *last_draw = Some(Instant::now());
+                return;
+            }
+        }
+        *last_draw = Some(Instant::now());
+        self.drawn.store(true, Ordering::Relaxed);
+        self.draw(progress);
+    }
+    
+    fn finish(&self, progress: &Progress) {
+        // Nothing was drawn for searches that finished within one redraw interval
+        if self.drawn.load(Ordering::Relaxed) {
+            self.draw(progress);
+            eprintln!();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_eta() {
+        let mut progress = Progress {
+            states: 25,
+            total_states: 100,
+            layer: 3,
+            layers: 5,
+            best_cost: None,
+            elapsed: Duration::from_secs(2),
+        };
+        assert_eq!(progress.fraction(), 0.25);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(6)));
+        
+        progress.states = 0;
+        assert_eq!(progress.eta(), None);
+        progress.total_states = 0;
+        assert_eq!(progress.fraction(), 0.0);
+    }
+    
+    #[test]
+    fn test_quick_searches_draw_nothing() {
+        let progress = Progress {
+            states: 0,
+            total_states: 100,
+            layer: 5,
+            layers: 5,
+            best_cost: None,
+            elapsed: Duration::ZERO,
+        };
+        let bar = ProgressBar::with_interval(Duration::from_secs(3600));
+        bar.update(&progress);
+        bar.update(&progress);
+        assert!(!bar.drawn.load(Ordering::Relaxed));
+        
+        // Once an interval has passed since the first report, the next one draws
+        let bar = ProgressBar::with_interval(Duration::ZERO);
+        bar.update(&progress);
+        assert!(!bar.drawn.load(Ordering::Relaxed));
+        bar.update(&progress);
+        assert!(bar.drawn.load(Ordering::Relaxed));
+    }
+}
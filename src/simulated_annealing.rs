@@ -0,0 +1,343 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::cancel::CancelToken;
+use crate::heuristics::{rotate_to_start, Construction, ConstructionHeuristic};
+use crate::progress::{ProgressObserver, SearchProgress};
+use crate::rng::Rng;
+use crate::tsp_solver::{tour_cost, ConvergencePoint};
+
+/// Final temperature as a fraction of the initial one (geometric cooling)
+const FINAL_TEMPERATURE_RATIO: f64 = 1e-4;
+
+/// Moves per acceptance-rate window (adaptive cooling)
+const ADAPTIVE_WINDOW: usize = 1000;
+
+/// Samples kept in the convergence trace
+const TRACE_POINTS: usize = 1000;
+
+/// Temperature schedule
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cooling {
+    /// T = T0 · ratio^progress, progress being the used share of the budget
+    Geometric,
+    /// Every window, cool if more moves were accepted than a decaying target rate, else reheat
+    Adaptive,
+}
+
+impl FromStr for Cooling {
+    type Err = String;
+    
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "geometric" => Ok(Cooling::Geometric),
+            "adaptive" => Ok(Cooling::Adaptive),
+            _ => Err(format!("unknown cooling schedule '{}'", name)),
+        }
+    }
+}
+
+pub struct SimulatedAnnealing {
+    distance_matrix: Vec<Vec<f64>>,
+    n: usize,
+    seed: u64,
+    iterations: usize,
+    time_limit: Option<Duration>,
+    cancel: CancelToken,
+    observer: Option<Arc<dyn ProgressObserver>>,
+    cooling: Cooling,
+    trace: Vec<ConvergencePoint>,
+}
+
+impl SimulatedAnnealing {
+    pub fn new(distance_matrix: Vec<Vec<f64>>) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            n,
+            seed: 42,
+            iterations: 1_000_000,
+            time_limit: None,
+            cancel: CancelToken::new(),
+            observer: None,
+            cooling: Cooling::Geometric,
+            trace: Vec::new(),
+        }
+    }
+    
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+    
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+    
+    /// Stop annealing once `cancel` fires and return the best tour so far
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+    
+    /// Report iterations done and the best cost so far to `observer`
+    pub fn with_progress(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+    
+    pub fn with_cooling(mut self, cooling: Cooling) -> Self {
+        self.cooling = cooling;
+        self
+    }
+    
+    /// Cost of the current and best tour over the last run
+    pub fn trace(&self) -> &[ConvergencePoint] {
+        &self.trace
+    }
+    
+    /// Anneal from a nearest neighbour tour until the iteration or time budget runs out;
+    /// returns the best tour seen
+    pub fn solve(&mut self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        self.trace.clear();
+        
+        if self.n < 4 {
+            let path: Vec<usize> = (0..self.n).collect();
+            return Ok((tour_cost(&self.distance_matrix, &path), path));
+        }
+        
+        let mut rng = Rng::new(self.seed);
+        let mut path = ConstructionHeuristic::new(self.distance_matrix.clone(), Construction::NearestNeighbour)
+            .nearest_neighbour_from(0);
+        let mut cost = tour_cost(&self.distance_matrix, &path);
+        let mut best_cost = cost;
+        let mut best_path = path.clone();
+        
+        let initial_temperature = self.initial_temperature(&path, &mut rng);
+        let mut temperature = initial_temperature;
+        let mut accepted_in_window = 0;
+        
+        println!("  • Annealing {} cities from {:.1}, T0 = {:.3}, {:?} cooling (seed {})",
+                self.n, cost, initial_temperature, self.cooling, self.seed);
+        
+        let start = Instant::now();
+        let reporter = SearchProgress::new(self.observer.clone(), self.iterations as u64);
+        let trace_every = (self.iterations / TRACE_POINTS).max(1);
+        let mut iteration = 0;
+        
+        while iteration < self.iterations {
+            let progress = self.progress(iteration, start);
+            if progress >= 1.0 || self.cancel.is_cancelled() {
+                break;
+            }
+            
+            let mv = self.random_move(&mut rng);
+            let delta = self.delta(&path, mv);
+            
+            if delta <= 0.0 || rng.next_f64() < (-delta / temperature).exp() {
+                apply(&mut path, mv);
+                cost += delta;
+                accepted_in_window += 1;
+                
+                if cost < best_cost - 1e-9 {
+                    best_cost = cost;
+                    best_path.copy_from_slice(&path);
+                }
+            }
+            
+            iteration += 1;
+            
+            match self.cooling {
+                Cooling::Geometric => {
+                    temperature = initial_temperature * FINAL_TEMPERATURE_RATIO.powf(progress);
+                }
+                Cooling::Adaptive => {
+                    if iteration % ADAPTIVE_WINDOW == 0 {
+                        // Target acceptance decays geometrically from 50% to 0.2%
+                        let target = 0.5 * (0.004f64).powf(progress);
+                        let rate = accepted_in_window as f64 / ADAPTIVE_WINDOW as f64;
+                        temperature *= if rate > target { 0.9 } else { 1.1 };
+                        accepted_in_window = 0;
+                    }
+                }
+            }
+            
+            if iteration % trace_every == 0 {
+                self.trace.push(ConvergencePoint { iteration, current: cost, best: best_cost });
+                reporter.update(iteration as u64, best_cost);
+                
+                if verbose && iteration % (trace_every * 100) == 0 {
+                    println!("    • Iteration {}: T = {:.4}, current {:.1}, best {:.1}",
+                            iteration, temperature, cost, best_cost);
+                }
+            }
+        }
+        
+        // Finish the progress bar before printing under it
+        let path = rotate_to_start(best_path);
+        let cost = tour_cost(&self.distance_matrix, &path);
+        reporter.finish(iteration as u64, cost);
+        println!("  • {} iterations in {:.2?}", iteration, start.elapsed());
+        
+        Ok((cost, path))
+    }
+    
+    /// Share of the budget used, by iterations or time, whichever is further along
+    fn progress(&self, iteration: usize, start: Instant) -> f64 {
+        let by_iterations = iteration as f64 / self.iterations as f64;
+        match self.time_limit {
+            Some(limit) => by_iterations.max(start.elapsed().as_secs_f64() / limit.as_secs_f64().max(1e-9)),
+            None => by_iterations,
+        }
+    }
+    
+    /// Temperature at which an average uphill move is accepted half of the time
+    fn initial_temperature(&self, path: &[usize], rng: &mut Rng) -> f64 {
+        let uphill: Vec<f64> = (0..200)
+            .map(|_| self.delta(path, self.random_move(rng)))
+            .filter(|&delta| delta > 0.0)
+            .collect();
+        
+        if uphill.is_empty() {
+            return 1.0;
+        }
+        let mean = uphill.iter().sum::<f64>() / uphill.len() as f64;
+        mean / std::f64::consts::LN_2
+    }
+    
+    fn random_move(&self, rng: &mut Rng) -> Move {
+        let i = rng.below(self.n);
+        let mut j = rng.below(self.n - 1);
+        if j >= i {
+            j += 1;
+        }
+        
+        match rng.below(3) {
+            0 => Move::TwoOpt(i.min(j), i.max(j)),
+            1 => Move::Swap(i.min(j), i.max(j)),
+            _ => Move::Insert(i, j),
+        }
+    }
+    
+    /// Cost change of applying `mv` to `path`
+    fn delta(&self, path: &[usize], mv: Move) -> f64 {
+        let d = &self.distance_matrix;
+        let n = self.n;
+        let at = |k: usize| path[k % n];
+        
+        match mv {
+            Move::TwoOpt(i, j) => {
+                if i == 0 && j == n - 1 {
+                    // Whole tour reversed, closing edge included
+                    return reversal_change(d, path, 0, n - 1) + d[path[0]][path[n - 1]] - d[path[n - 1]][path[0]];
+                }
+                let (a, b, c, e) = (at(i + n - 1), path[i], path[j], at(j + 1));
+                d[a][c] + d[b][e] - d[a][b] - d[c][e] + reversal_change(d, path, i, j)
+            }
+            Move::Swap(i, j) => {
+                // Edges k -> k+1 touching either position, each counted once
+                let mut edges = [(i + n - 1) % n, i, (j + n - 1) % n, j];
+                edges.sort_unstable();
+                let city = |k: usize| {
+                    let k = k % n;
+                    if k == i { path[j] } else if k == j { path[i] } else { path[k] }
+                };
+                
+                let mut change = 0.0;
+                for (idx, &k) in edges.iter().enumerate() {
+                    if idx > 0 && edges[idx - 1] == k {
+                        continue;
+                    }
+                    change += d[city(k)][city(k + 1)] - d[at(k)][at(k + 1)];
+                }
+                change
+            }
+            Move::Insert(from, to) => {
+                // City at `from` is removed and re-inserted so that it ends at index `to`
+                let x = path[from];
+                let (p, q) = (at(from + n - 1), at(from + 1));
+                let removed = d[p][x] + d[x][q] - d[p][q];
+                
+                let (u, v) = if to > from { (path[to], at(to + 1)) } else { (at(to + n - 1), path[to]) };
+                if u == x || v == x {
+                    return 0.0;
+                }
+                d[u][x] + d[x][v] - d[u][v] - removed
+            }
+        }
+    }
+}
+
+/// Neighbourhood moves on positions of the path
+#[derive(Clone, Copy, Debug)]
+enum Move {
+    /// Reverse path[i..=j]
+    TwoOpt(usize, usize),
+    /// Exchange the cities at i and j
+    Swap(usize, usize),
+    /// Move the city at the first index to the second index
+    Insert(usize, usize),
+}
+
+fn apply(path: &mut [usize], mv: Move) {
+    match mv {
+        Move::TwoOpt(i, j) => path[i..=j].reverse(),
+        Move::Swap(i, j) => path.swap(i, j),
+        Move::Insert(from, to) if from < to => path[from..=to].rotate_left(1),
+        Move::Insert(from, to) => path[to..=from].rotate_right(1),
+    }
+}
+
+/// Extra length of walking path[i..=j] backwards (zero on symmetric matrices)
+fn reversal_change(d: &[Vec<f64>], path: &[usize], i: usize, j: usize) -> f64 {
+    (i..j).map(|k| d[path[k + 1]][path[k]] - d[path[k]][path[k + 1]]).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pseudo_random_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    #[test]
+    fn test_move_deltas_match_recomputed_cost() {
+        let matrix = pseudo_random_matrix(9, false);
+        let sa = SimulatedAnnealing::new(matrix.clone());
+        let mut rng = Rng::new(3);
+        let mut path: Vec<usize> = (0..9).collect();
+        
+        for _ in 0..2000 {
+            let mv = sa.random_move(&mut rng);
+            let before = tour_cost(&matrix, &path);
+            let delta = sa.delta(&path, mv);
+            apply(&mut path, mv);
+            assert!((tour_cost(&matrix, &path) - before - delta).abs() < 1e-9, "{:?}", mv);
+        }
+    }
+    
+    #[test]
+    fn test_seed_is_reproducible_and_near_optimal() {
+        let matrix = pseudo_random_matrix(10, true);
+        let (optimum, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        
+        let run = |seed| SimulatedAnnealing::new(matrix.clone())
+            .with_seed(seed)
+            .with_iterations(50_000)
+            .solve(false)
+            .unwrap();
+        let (cost, path) = run(5);
+        
+        assert_eq!(run(5), (cost, path.clone()));
+        assert!(cost >= optimum - 1e-9);
+        assert!(cost <= optimum * 1.1);
+    }
+}
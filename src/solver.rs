@@ -0,0 +1,1011 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::ant_colony::AntColony;
+use crate::branch_and_bound::BranchAndBound;
+use crate::cancel::CancelToken;
+use crate::christofides::Christofides;
+use crate::genetic::GeneticAlgorithm;
+use crate::heuristics::{Construction, ConstructionHeuristic};
+use crate::lin_kernighan::LinKernighan;
+use crate::multi_salesman::MultiSalesman;
+use crate::open_path::PathAsTour;
+use crate::prize_collecting::PrizeCollecting;
+use crate::progress::ProgressObserver;
+use crate::simulated_annealing::SimulatedAnnealing;
+use crate::time_windows::TimeWindowSolver;
+use crate::tsp_solver::{ConvergencePoint, Route, TieBreak, TourObjective, TSPSolver, route_cost};
+use crate::vehicle_routing::VehicleRouting;
+use crate::TSPError;
+
+/// Name of the default algorithm, the Held-Karp DP
+pub const HELD_KARP: &str = "held-karp";
+
+/// Which routes a solver reports besides the best one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Listing {
+    /// Only the best route
+    Best,
+    /// The k best distinct routes
+    KBest(usize),
+    /// Every optimal route, up to a cap
+    CoOptimal(usize),
+}
+
+/// Problem instance: the distance matrix and what kind of route to find in it
+#[derive(Clone, Debug)]
+pub struct Problem {
+    pub matrix: Vec<Vec<f64>>,
+    pub start: usize,
+    pub route: Route,
+    pub objective: TourObjective,
+    /// (before, after) pairs
+    pub precedence: Vec<(usize, usize)>,
+    /// Cluster id per city (empty: every city is its own cluster)
+    pub clusters: Vec<usize>,
+    pub tie_break: TieBreak,
+    pub listing: Listing,
+    /// City names for error messages (empty: cities are numbered)
+    pub names: Vec<String>,
+}
+
+impl Problem {
+    /// Closed tour from city 0 with the shortest total distance
+    pub fn new(matrix: Vec<Vec<f64>>) -> Self {
+        Self {
+            matrix,
+            start: 0,
+            route: Route::Tour,
+            objective: TourObjective::MinTotal,
+            precedence: Vec::new(),
+            clusters: Vec::new(),
+            tie_break: TieBreak::LoopOrder,
+            listing: Listing::Best,
+            names: Vec::new(),
+        }
+    }
+    
+    /// Shortest route through every city, without constraints or listings; the tie-break
+    /// only matters to solvers that see equally good routes
+    pub fn is_plain(&self) -> bool {
+        self.objective == TourObjective::MinTotal
+            && self.precedence.is_empty()
+            && self.clusters.is_empty()
+            && self.listing == Listing::Best
+    }
+}
+
+/// How much a solution is known to be worth
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    /// Proven optimal
+    Optimal,
+    /// Heuristic tour, no claim about the optimum
+    Heuristic,
+    /// An exact search stopped early (time limit or cancellation); not proven optimal
+    Stopped,
+}
+
+/// Result of one solver run
+#[derive(Clone, Debug)]
+pub struct Solution {
+    /// Cities in visiting order. Open paths begin at their start city; closed tours may
+    /// begin at any city (`from_start` rotates them to the start city).
+    pub tour: Vec<usize>,
+    pub cost: f64,
+    pub status: Status,
+    /// Lower bound on the optimum known to the solver
+    pub bound: Option<f64>,
+    pub elapsed: Duration,
+    /// Search statistics, e.g. ("states", 4980736)
+    pub stats: Vec<(&'static str, u64)>,
+    /// Convergence of metaheuristics, empty otherwise
+    pub trace: Vec<ConvergencePoint>,
+    /// Routes listed for `Listing::KBest` or `Listing::CoOptimal`, best first
+    pub alternatives: Vec<(f64, Vec<usize>)>,
+    /// Number of optimal routes, for `Listing::CoOptimal`
+    pub optimal_count: Option<u64>,
+}
+
+impl Solution {
+    pub fn new((cost, tour): (f64, Vec<usize>), status: Status, started: Instant) -> Self {
+        Self {
+            tour,
+            cost,
+            status,
+            bound: (status == Status::Optimal).then_some(cost),
+            elapsed: started.elapsed(),
+            stats: Vec::new(),
+            trace: Vec::new(),
+            alternatives: Vec::new(),
+            optimal_count: None,
+        }
+    }
+    
+    pub fn with_stat(mut self, name: &'static str, value: u64) -> Self {
+        self.stats.push((name, value));
+        self
+    }
+    
+    /// Keep a metaheuristic's convergence trace, and its length in iterations as a stat
+    fn with_trace(mut self, trace: &[ConvergencePoint]) -> Self {
+        if let Some(last) = trace.last() {
+            self.stats.push(("iterations", last.iteration as u64));
+        }
+        self.trace = trace.to_vec();
+        self
+    }
+}
+
+/// Setting that belongs to one algorithm, passed on the command line as `--NAME VALUE`
+#[derive(Clone, Copy, Debug)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub help: &'static str,
+    /// Value used when the parameter is not given (None: the algorithm decides)
+    pub default: Option<&'static str>,
+    /// Accepted values (empty: anything that parses)
+    pub choices: &'static [&'static str],
+}
+
+const NN_STARTS: Parameter = Parameter {
+    name: "nn-starts",
+    help: "Start cities tried by nearest neighbour, spread evenly (default: every city)",
+    default: None,
+    choices: &[],
+};
+const CANDIDATES: Parameter = Parameter {
+    name: "candidates",
+    help: "Candidate sets for Lin-Kernighan: nearest neighbours or alpha-nearness",
+    default: Some("nearest"),
+    choices: &["nearest", "alpha"],
+};
+const COOLING: Parameter = Parameter {
+    name: "cooling",
+    help: "Cooling schedule for simulated annealing: geometric decay or a target acceptance rate",
+    default: Some("geometric"),
+    choices: &["geometric", "adaptive"],
+};
+const POPULATION: Parameter = Parameter {
+    name: "population",
+    help: "Population size for the genetic algorithm",
+    default: Some("100"),
+    choices: &[],
+};
+const GENERATIONS: Parameter = Parameter {
+    name: "generations",
+    help: "Generation budget for the genetic algorithm",
+    default: Some("500"),
+    choices: &[],
+};
+const MUTATION_RATE: Parameter = Parameter {
+    name: "mutation-rate",
+    help: "Chance that a child is mutated (swap or inversion)",
+    default: Some("0.1"),
+    choices: &[],
+};
+const CROSSOVER: Parameter = Parameter {
+    name: "crossover",
+    help: "Crossover operator for the genetic algorithm: order, partially mapped or edge recombination",
+    default: Some("ox"),
+    choices: &["ox", "pmx", "erx"],
+};
+const COLONY: Parameter = Parameter {
+    name: "colony",
+    help: "Pheromone update rule for ant colony optimization: Ant Colony System or MAX-MIN",
+    default: Some("acs"),
+    choices: &["acs", "mmas"],
+};
+const ANTS: Parameter = Parameter {
+    name: "ants",
+    help: "Ants per iteration",
+    default: Some("20"),
+    choices: &[],
+};
+const ALPHA: Parameter = Parameter {
+    name: "alpha",
+    help: "Pheromone weight when ants choose the next city",
+    default: Some("1.0"),
+    choices: &[],
+};
+const BETA: Parameter = Parameter {
+    name: "beta",
+    help: "Inverse-distance weight when ants choose the next city",
+    default: Some("3.0"),
+    choices: &[],
+};
+const EVAPORATION: Parameter = Parameter {
+    name: "evaporation",
+    help: "Pheromone evaporation rate",
+    default: Some("0.1"),
+    choices: &[],
+};
+
+/// Settings shared by all algorithms, plus the values of their own `parameters()`
+#[derive(Clone)]
+pub struct Options {
+    pub verbose: bool,
+    /// Worker threads for the DP (0 = all cores)
+    pub threads: usize,
+    pub seed: u64,
+    /// Iteration budget (default per algorithm when None)
+    pub iterations: Option<usize>,
+    pub time_limit: Option<Duration>,
+    pub cancel: CancelToken,
+    pub progress: Option<Arc<dyn ProgressObserver>>,
+    /// Local search on every ant's tour
+    pub local_search: bool,
+    /// Algorithm parameters by name; missing ones take their default
+    pub params: BTreeMap<String, String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            verbose: false,
+            threads: 1,
+            seed: 42,
+            iterations: None,
+            time_limit: None,
+            cancel: CancelToken::new(),
+            progress: None,
+            local_search: false,
+            params: BTreeMap::new(),
+        }
+    }
+}
+
+impl Options {
+    /// Value of a parameter with a default
+    pub fn param<T: FromStr>(&self, parameter: &Parameter) -> Result<T> where T::Err: Display {
+        self.optional_param(parameter)?.ok_or_else(|| {
+            TSPError::InvalidFormat(format!("--{} needs a value", parameter.name)).into()
+        })
+    }
+    
+    /// Value of a parameter, None when it has no default and was not given
+    pub fn optional_param<T: FromStr>(&self, parameter: &Parameter) -> Result<Option<T>> where T::Err: Display {
+        let Some(value) = self.params.get(parameter.name).map(String::as_str).or(parameter.default) else {
+            return Ok(None);
+        };
+        if !parameter.choices.is_empty() && !parameter.choices.contains(&value) {
+            return Err(TSPError::InvalidFormat(
+                format!("--{} must be one of {}, not '{}'", parameter.name, parameter.choices.join(", "), value)
+            ).into());
+        }
+        value.parse().map(Some).map_err(|e| {
+            TSPError::InvalidFormat(format!("Invalid value '{}' for --{}: {}", value, parameter.name, e)).into()
+        })
+    }
+}
+
+/// A tour algorithm. Implementations provide `solve_tour`; open paths reach it as tours
+/// through an extra city, and constrained problems are refused unless `solve` is overridden.
+pub trait Solver: Sync {
+    /// Name used to select the algorithm on the command line
+    fn name(&self) -> &'static str;
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+    
+    /// Human-readable name for progress output
+    fn label(&self) -> &'static str;
+    
+    /// One line for `--list-algorithms`
+    fn description(&self) -> &'static str;
+    
+    /// Whether the algorithm proves its tour optimal
+    fn is_exact(&self) -> bool {
+        false
+    }
+    
+    /// Largest instance the algorithm accepts (None = no limit)
+    fn max_cities(&self) -> Option<usize> {
+        None
+    }
+    
+    /// Whether the algorithm can respect time windows from the input
+    fn supports_time_windows(&self) -> bool {
+        false
+    }
+    
+    /// Whether open paths (solved as tours through an extra city) are supported
+    fn supports_open_paths(&self) -> bool {
+        true
+    }
+    
+    /// Whether precedence pairs, clusters, other objectives, tie-breaks and tour listings
+    /// are supported
+    fn supports_constraints(&self) -> bool {
+        false
+    }
+    
+    /// What the second series of the convergence chart shows
+    fn trace_series(&self) -> &'static str {
+        "Current"
+    }
+    
+    /// Settings of this algorithm, read from `Options::params`
+    fn parameters(&self) -> &'static [Parameter] {
+        &[]
+    }
+    
+    /// Closed tour from city 0 through every city of `matrix`
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution>;
+    
+    /// Route for `problem`, from its start city
+    fn solve(&self, problem: &Problem, options: &Options) -> Result<Solution> {
+        check_supported(self, problem)?;
+        solve_as_tour(problem, |matrix| self.solve_tour(matrix, options))
+    }
+    
+    /// Routes for several salesmen: one tour through copies of the depot, cut into routes
+    fn solve_salesmen(&self, mtsp: &MultiSalesman, options: &Options) -> Result<(f64, Vec<Vec<usize>>)> {
+        let tour = self.solve_tour(mtsp.depot_copies()?, options)?.tour;
+        let routes = mtsp.split_tour(&tour);
+        Ok((mtsp.score(&routes), routes))
+    }
+    
+    /// Tour that respects the time windows of `instance`
+    fn solve_time_windows(&self, _instance: &TimeWindowSolver, _options: &Options) -> Result<Solution> {
+        Err(time_windows_unsupported(self))
+    }
+    
+    /// Vehicle routes: the split is shared, every route is then re-solved with this algorithm
+    /// (routes beyond `max_cities` fall back to local search)
+    fn solve_vehicle_routing(&self, vrp: &VehicleRouting, options: &Options) -> Result<(f64, Vec<Vec<usize>>)> {
+        vrp.solve_with(options.verbose, |matrix| match self.max_cities() {
+            Some(max) if matrix.len() > max => VehicleRouting::route_tour(matrix),
+            _ => Ok(self.solve_tour(matrix, options)?.tour),
+        })
+    }
+    
+    /// Prize-collecting tour by greedy insertion and local search
+    fn solve_prizes(&self, prizes: &PrizeCollecting, options: &Options) -> Result<(f64, Vec<usize>)> {
+        prizes.solve_heuristic(options.verbose)
+    }
+}
+
+/// Refuse problems the default `Solver::solve` cannot pass to `solve_tour`
+fn check_supported<S: Solver + ?Sized>(solver: &S, problem: &Problem) -> Result<()> {
+    if !problem.is_plain() {
+        return Err(TSPError::GraphValidation(
+            format!("{} only finds the shortest route through every city (use {})", solver.label(), HELD_KARP)
+        ).into());
+    }
+    if !problem.route.is_closed() && !solver.supports_open_paths() {
+        return Err(TSPError::GraphValidation(
+            format!("{} only builds closed tours", solver.label())
+        ).into());
+    }
+    Ok(())
+}
+
+fn time_windows_unsupported<S: Solver + ?Sized>(solver: &S) -> anyhow::Error {
+    TSPError::GraphValidation(
+        format!("Time windows are not supported with {} (use held-karp or cheapest-insertion)", solver.label())
+    ).into()
+}
+
+/// Solve a plain problem with a closed-tour solver: open paths become tours through an
+/// extra city and are cut back into paths. Closed tours stay in city 0 order.
+fn solve_as_tour(problem: &Problem, solve: impl FnOnce(Vec<Vec<f64>>) -> Result<Solution>) -> Result<Solution> {
+    if problem.route.is_closed() {
+        return solve(problem.matrix.clone());
+    }
+    
+    let instance = PathAsTour::new(&problem.matrix, problem.start, problem.route);
+    let mut solution = solve(instance.matrix.clone())?;
+    for point in &mut solution.trace {
+        point.current -= instance.offset();
+        point.best -= instance.offset();
+    }
+    solution.bound = solution.bound.map(|bound| bound - instance.offset());
+    solution.tour = instance.to_path(&solution.tour);
+    solution.cost = route_cost(&problem.matrix, &solution.tour, problem.route);
+    Ok(solution)
+}
+
+/// Every algorithm, in the order `--list-algorithms` shows them
+pub static ALGORITHMS: &[&dyn Solver] = &[
+    &HeldKarp,
+    &BranchAndBoundSearch,
+    &ConstructionSolver::NEAREST_NEIGHBOUR,
+    &ConstructionSolver::CHEAPEST_INSERTION,
+    &ConstructionSolver::FARTHEST_INSERTION,
+    &ConstructionSolver::NEAREST_INSERTION,
+    &LinKernighanSearch,
+    &Annealing,
+    &Genetic,
+    &Ants,
+    &ChristofidesApproximation,
+];
+
+/// Algorithm by name or alias
+pub fn find(name: &str) -> Result<&'static dyn Solver> {
+    ALGORITHMS.iter()
+        .copied()
+        .find(|solver| solver.name() == name || solver.aliases().contains(&name))
+        .ok_or_else(|| {
+            let names: Vec<&str> = ALGORITHMS.iter().map(|solver| solver.name()).collect();
+            TSPError::GraphValidation(
+                format!("Unknown algorithm '{}' (algorithms: {})", name, names.join(", "))
+            ).into()
+        })
+}
+
+/// Lin-Kernighan tour for an exact DP that ran out of time; the caller certifies it
+/// with a lower bound
+fn held_karp_fallback(matrix: Vec<Vec<f64>>, verbose: bool) -> Result<(f64, Vec<usize>)> {
+    println!("Time limit reached before the DP finished, using Lin-Kernighan instead...");
+    LinKernighan::new(matrix).solve(verbose)
+}
+
+fn is_cancelled(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<TSPError>(), Some(TSPError::Cancelled(_)))
+}
+
+struct HeldKarp;
+
+impl Solver for HeldKarp {
+    fn name(&self) -> &'static str {
+        HELD_KARP
+    }
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        &["dp"]
+    }
+    
+    fn label(&self) -> &'static str {
+        "Dynamic Programming"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Exact bitmask dynamic programming"
+    }
+    
+    fn is_exact(&self) -> bool {
+        true
+    }
+    
+    fn max_cities(&self) -> Option<usize> {
+        Some(25)
+    }
+    
+    fn supports_time_windows(&self) -> bool {
+        true
+    }
+    
+    fn supports_constraints(&self) -> bool {
+        true
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        self.solve(&Problem::new(matrix), options)
+    }
+    
+    /// The DP handles every route shape and constraint itself; plain problems that run out
+    /// of time fall back to Lin-Kernighan
+    fn solve(&self, problem: &Problem, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let mut solver = TSPSolver::new(problem.matrix.clone())
+            .with_start(problem.start)
+            .with_route(problem.route)
+            .with_objective(problem.objective)
+            .with_precedence(problem.precedence.clone())
+            .with_clusters(problem.clusters.clone())
+            .with_tie_break(problem.tie_break.clone())
+            .with_cancel(options.cancel.clone());
+        if let Some(observer) = &options.progress {
+            solver = solver.with_progress(observer.clone());
+        }
+        
+        let mut alternatives = Vec::new();
+        let mut optimal_count = None;
+        let result = match problem.listing {
+            Listing::Best => solver.solve_parallel(options.verbose, options.threads),
+            Listing::KBest(k) => solver.solve_k_best(k, options.verbose).map(|routes| {
+                alternatives = routes;
+                alternatives[0].clone()
+            }),
+            Listing::CoOptimal(cap) => solver.solve_co_optimal(cap, options.verbose).map(|optimal| {
+                optimal_count = Some(optimal.count);
+                alternatives = optimal.routes.into_iter().map(|route| (optimal.cost, route)).collect();
+                alternatives[0].clone()
+            }),
+        };
+        
+        match result {
+            Err(error) if is_cancelled(&error) && problem.is_plain() => solve_as_tour(problem, |matrix| {
+                Ok(Solution::new(held_karp_fallback(matrix, options.verbose)?, Status::Stopped, started))
+            }),
+            Err(error) if is_cancelled(&error) => Err(error.context(
+                "Time limit reached; precedence, clusters, other objectives and tour listings have no heuristic fallback"
+            )),
+            result => {
+                let mut solution = Solution::new(result?, Status::Optimal, started);
+                if solver.states() > 0 {
+                    solution = solution.with_stat("states", solver.states());
+                }
+                solution.alternatives = alternatives;
+                solution.optimal_count = optimal_count;
+                Ok(solution)
+            }
+        }
+    }
+    
+    /// Exact subset DP over the routes of every salesman
+    fn solve_salesmen(&self, mtsp: &MultiSalesman, options: &Options) -> Result<(f64, Vec<Vec<usize>>)> {
+        mtsp.solve_exact(options.verbose)
+    }
+    
+    /// DP over (visited set, last city) labels that keeps the earliest arrival times
+    fn solve_time_windows(&self, instance: &TimeWindowSolver, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        Ok(Solution::new(instance.solve(options.verbose)?, Status::Optimal, started))
+    }
+    
+    /// Short routes are solved exactly, longer ones by local search
+    fn solve_vehicle_routing(&self, vrp: &VehicleRouting, options: &Options) -> Result<(f64, Vec<Vec<usize>>)> {
+        vrp.solve(options.verbose)
+    }
+    
+    /// Exact subset DP on small inputs, greedy insertion beyond
+    fn solve_prizes(&self, prizes: &PrizeCollecting, options: &Options) -> Result<(f64, Vec<usize>)> {
+        prizes.solve(options.verbose)
+    }
+}
+
+struct BranchAndBoundSearch;
+
+impl Solver for BranchAndBoundSearch {
+    fn name(&self) -> &'static str {
+        "branch-and-bound"
+    }
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        &["bnb"]
+    }
+    
+    fn label(&self) -> &'static str {
+        "Branch and Bound"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Exact depth-first branch and bound"
+    }
+    
+    fn is_exact(&self) -> bool {
+        true
+    }
+    
+    fn max_cities(&self) -> Option<usize> {
+        Some(60)
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let mut search = BranchAndBound::new(matrix).with_cancel(options.cancel.clone());
+        if let Some(observer) = &options.progress {
+            search = search.with_progress(observer.clone());
+        }
+        let result = search.solve(options.verbose)?;
+        let status = if search.is_proven() { Status::Optimal } else { Status::Stopped };
+        Ok(Solution::new(result, status, started).with_stat("nodes", search.nodes() as u64))
+    }
+}
+
+/// Tour construction heuristic; one registry entry per `Construction`
+struct ConstructionSolver {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    label: &'static str,
+    description: &'static str,
+    method: Construction,
+    parameters: &'static [Parameter],
+}
+
+impl ConstructionSolver {
+    const NEAREST_NEIGHBOUR: Self = Self {
+        name: "nearest-neighbour",
+        aliases: &["nn"],
+        label: "Nearest Neighbour",
+        description: "Nearest neighbour from every start city (or --nn-starts of them)",
+        method: Construction::NearestNeighbour,
+        parameters: &[NN_STARTS],
+    };
+    const CHEAPEST_INSERTION: Self = Self {
+        name: "cheapest-insertion",
+        aliases: &[],
+        label: "Cheapest Insertion",
+        description: "Cheapest insertion heuristic",
+        method: Construction::CheapestInsertion,
+        parameters: &[],
+    };
+    const FARTHEST_INSERTION: Self = Self {
+        name: "farthest-insertion",
+        aliases: &[],
+        label: "Farthest Insertion",
+        description: "Farthest insertion heuristic",
+        method: Construction::FarthestInsertion,
+        parameters: &[],
+    };
+    const NEAREST_INSERTION: Self = Self {
+        name: "nearest-insertion",
+        aliases: &[],
+        label: "Nearest Insertion",
+        description: "Nearest insertion heuristic",
+        method: Construction::NearestInsertion,
+        parameters: &[],
+    };
+}
+
+impl Solver for ConstructionSolver {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+    
+    fn label(&self) -> &'static str {
+        self.label
+    }
+    
+    fn description(&self) -> &'static str {
+        self.description
+    }
+    
+    fn supports_time_windows(&self) -> bool {
+        self.method == Construction::CheapestInsertion
+    }
+    
+    fn parameters(&self) -> &'static [Parameter] {
+        self.parameters
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let result = ConstructionHeuristic::new(matrix, self.method)
+            .with_starts(options.optional_param(&NN_STARTS)?)
+            .solve(options.verbose)?;
+        Ok(Solution::new(result, Status::Heuristic, started))
+    }
+    
+    /// Cheapest insertion that only inserts where every later arrival still fits
+    fn solve_time_windows(&self, instance: &TimeWindowSolver, options: &Options) -> Result<Solution> {
+        if !self.supports_time_windows() {
+            return Err(time_windows_unsupported(self));
+        }
+        let started = Instant::now();
+        Ok(Solution::new(instance.solve_heuristic(options.verbose)?, Status::Heuristic, started))
+    }
+}
+
+struct LinKernighanSearch;
+
+impl Solver for LinKernighanSearch {
+    fn name(&self) -> &'static str {
+        "lin-kernighan"
+    }
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        &["lk"]
+    }
+    
+    fn label(&self) -> &'static str {
+        "Lin-Kernighan"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Lin-Kernighan style variable-depth local search"
+    }
+    
+    fn parameters(&self) -> &'static [Parameter] {
+        &[CANDIDATES]
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let mut search = LinKernighan::new(matrix)
+            .with_candidates(options.param(&CANDIDATES)?)
+            .with_cancel(options.cancel.clone());
+        if let Some(observer) = &options.progress {
+            search = search.with_progress(observer.clone());
+        }
+        let result = search.solve(options.verbose)?;
+        Ok(Solution::new(result, Status::Heuristic, started))
+    }
+}
+
+struct Annealing;
+
+impl Solver for Annealing {
+    fn name(&self) -> &'static str {
+        "simulated-annealing"
+    }
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        &["sa"]
+    }
+    
+    fn label(&self) -> &'static str {
+        "Simulated Annealing"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Simulated annealing over 2-opt, swap and insertion moves"
+    }
+    
+    fn parameters(&self) -> &'static [Parameter] {
+        &[COOLING]
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let mut annealing = SimulatedAnnealing::new(matrix)
+            .with_seed(options.seed)
+            .with_iterations(options.iterations.unwrap_or(1_000_000))
+            .with_time_limit(options.time_limit)
+            .with_cancel(options.cancel.clone())
+            .with_cooling(options.param(&COOLING)?);
+        if let Some(observer) = &options.progress {
+            annealing = annealing.with_progress(observer.clone());
+        }
+        let result = annealing.solve(options.verbose)?;
+        Ok(Solution::new(result, Status::Heuristic, started).with_trace(annealing.trace()))
+    }
+}
+
+struct Genetic;
+
+impl Solver for Genetic {
+    fn name(&self) -> &'static str {
+        "genetic"
+    }
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ga"]
+    }
+    
+    fn label(&self) -> &'static str {
+        "Genetic Algorithm"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Genetic algorithm with tournament selection and elitism"
+    }
+    
+    fn trace_series(&self) -> &'static str {
+        "Population mean"
+    }
+    
+    fn parameters(&self) -> &'static [Parameter] {
+        &[POPULATION, GENERATIONS, MUTATION_RATE, CROSSOVER]
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let mut genetic = GeneticAlgorithm::new(matrix)
+            .with_seed(options.seed)
+            .with_population(options.param(&POPULATION)?)
+            .with_generations(options.param(&GENERATIONS)?)
+            .with_mutation_rate(options.param(&MUTATION_RATE)?)
+            .with_crossover(options.param(&CROSSOVER)?)
+            .with_time_limit(options.time_limit)
+            .with_cancel(options.cancel.clone());
+        if let Some(observer) = &options.progress {
+            genetic = genetic.with_progress(observer.clone());
+        }
+        let result = genetic.solve(options.verbose)?;
+        Ok(Solution::new(result, Status::Heuristic, started).with_trace(genetic.trace()))
+    }
+}
+
+struct Ants;
+
+impl Solver for Ants {
+    fn name(&self) -> &'static str {
+        "ant-colony"
+    }
+    
+    fn aliases(&self) -> &'static [&'static str] {
+        &["aco"]
+    }
+    
+    fn label(&self) -> &'static str {
+        "Ant Colony Optimization"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Ant colony optimization (ACS or MAX-MIN)"
+    }
+    
+    fn trace_series(&self) -> &'static str {
+        "Iteration best"
+    }
+    
+    fn parameters(&self) -> &'static [Parameter] {
+        &[COLONY, ANTS, ALPHA, BETA, EVAPORATION]
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let mut ants = AntColony::new(matrix)
+            .with_colony(options.param(&COLONY)?)
+            .with_seed(options.seed)
+            .with_ants(options.param(&ANTS)?)
+            .with_iterations(options.iterations.unwrap_or(500))
+            .with_weights(options.param(&ALPHA)?, options.param(&BETA)?)
+            .with_evaporation(options.param(&EVAPORATION)?)
+            .with_local_search(options.local_search)
+            .with_time_limit(options.time_limit)
+            .with_cancel(options.cancel.clone());
+        if let Some(observer) = &options.progress {
+            ants = ants.with_progress(observer.clone());
+        }
+        let result = ants.solve(options.verbose)?;
+        Ok(Solution::new(result, Status::Heuristic, started).with_trace(ants.trace()))
+    }
+}
+
+struct ChristofidesApproximation;
+
+impl Solver for ChristofidesApproximation {
+    fn name(&self) -> &'static str {
+        "christofides"
+    }
+    
+    fn label(&self) -> &'static str {
+        "Christofides"
+    }
+    
+    fn description(&self) -> &'static str {
+        "Christofides 1.5-approximation (symmetric, metric matrices only)"
+    }
+    
+    fn supports_open_paths(&self) -> bool {
+        false
+    }
+    
+    fn solve_tour(&self, matrix: Vec<Vec<f64>>, options: &Options) -> Result<Solution> {
+        let started = Instant::now();
+        let result = Christofides::new(matrix).solve(options.verbose)?;
+        Ok(Solution::new(result, Status::Heuristic, started))
+    }
+    
+    /// Closed tours only, so the matrix is the problem's own and the triangle inequality
+    /// check can name the cities
+    fn solve(&self, problem: &Problem, options: &Options) -> Result<Solution> {
+        check_supported(self, problem)?;
+        let started = Instant::now();
+        let result = Christofides::new(problem.matrix.clone())
+            .with_names(problem.names.clone())
+            .solve(options.verbose)?;
+        Ok(Solution::new(result, Status::Heuristic, started))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetic::Crossover;
+    use crate::vehicle_routing::Demands;
+    use crate::test_support::{points_matrix, pseudo_random_matrix, ProgressRecorder};
+    
+    #[test]
+    fn test_registry_names() {
+        for (i, solver) in ALGORITHMS.iter().enumerate() {
+            assert_eq!(find(solver.name()).unwrap().name(), solver.name());
+            for alias in solver.aliases() {
+                assert_eq!(find(alias).unwrap().name(), solver.name());
+            }
+            assert!(ALGORITHMS[..i].iter().all(|other| other.name() != solver.name()));
+        }
+        assert_eq!(find("dp").unwrap().name(), HELD_KARP);
+        assert!(find("simplex").is_err());
+    }
+    
+    fn short_runs() -> BTreeMap<String, String> {
+        BTreeMap::from([("generations".to_string(), "50".to_string())])
+    }
+    
+    #[test]
+    fn test_parameters() {
+        let mut names: Vec<&str> = ALGORITHMS.iter().flat_map(|s| s.parameters()).map(|p| p.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), ALGORITHMS.iter().map(|s| s.parameters().len()).sum::<usize>());
+        
+        let mut options = Options::default();
+        assert_eq!(options.param::<usize>(&POPULATION).unwrap(), 100);
+        assert_eq!(options.optional_param::<usize>(&NN_STARTS).unwrap(), None);
+        options.params.insert("population".to_string(), "fifty".to_string());
+        options.params.insert("crossover".to_string(), "cx".to_string());
+        assert!(options.param::<usize>(&POPULATION).is_err());
+        assert!(options.param::<Crossover>(&CROSSOVER).unwrap_err().to_string().contains("one of ox, pmx, erx"));
+        assert!(find("genetic").unwrap().solve_tour(points_matrix(6), &options).is_err());
+    }
+    
+    #[test]
+    fn test_every_algorithm_solves() {
+        let matrix = points_matrix(9);
+        let options = Options { iterations: Some(2000), params: short_runs(), ..Options::default() };
+        
+        for (start, route) in [(0, Route::Tour), (3, Route::Tour), (3, Route::Path { end: None }), (0, Route::FreePath)] {
+            let problem = Problem { start, route, ..Problem::new(matrix.clone()) };
+            let optimum = find(HELD_KARP).unwrap().solve(&problem, &options).unwrap();
+            assert_eq!(optimum.status, Status::Optimal);
+            assert!(optimum.stats.iter().any(|&(name, states)| name == "states" && states > 0));
+            
+            for solver in ALGORITHMS.iter().filter(|s| route.is_closed() || s.supports_open_paths()) {
+                let solution = solver.solve(&problem, &options).unwrap();
+                let mut cities = solution.tour.clone();
+                cities.sort();
+                assert_eq!(cities, (0..9).collect::<Vec<_>>(), "{}", solver.name());
+                assert!((route_cost(&matrix, &solution.tour, route) - solution.cost).abs() < 1e-9);
+                assert!(solution.cost >= optimum.cost - 1e-9);
+                assert_eq!(solution.status == Status::Optimal, solver.is_exact());
+                if matches!(route, Route::Path { .. }) {
+                    assert_eq!(solution.tour[0], start);
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_searches_report_their_best() {
+        let matrix = points_matrix(12);
+        for name in [HELD_KARP, "branch-and-bound", "lin-kernighan", "simulated-annealing", "genetic", "ant-colony"] {
+            let recorder = Arc::new(ProgressRecorder::default());
+            let options = Options {
+                iterations: Some(2000),
+                progress: Some(recorder.clone()),
+                params: short_runs(),
+                ..Options::default()
+            };
+            let solution = find(name).unwrap().solve_tour(matrix.clone(), &options).unwrap();
+            
+            let reports = recorder.0.lock().unwrap();
+            let best: Vec<f64> = reports.iter().map(|p| p.best_cost.expect(name)).collect();
+            assert!(best.len() > 1, "{}", name);
+            assert!(best.windows(2).all(|pair| pair[1] <= pair[0] + 1e-9), "{}: {:?}", name, best);
+            assert!((best[best.len() - 1] - solution.cost).abs() < 1e-9, "{}", name);
+        }
+    }
+    
+    #[test]
+    fn test_routes_use_the_algorithm() {
+        let demand = (0..14).map(|c| if c == 0 { 0.0 } else { 1.0 }).collect();
+        let vrp = VehicleRouting::new(points_matrix(14), Demands { demand, capacity: 5.0 });
+        let options = Options { iterations: Some(2000), params: short_runs(), ..Options::default() };
+        let (optimum, _) = find(HELD_KARP).unwrap().solve_vehicle_routing(&vrp, &options).unwrap();
+        
+        for solver in ALGORITHMS {
+            let (total, routes) = solver.solve_vehicle_routing(&vrp, &options).unwrap();
+            let mut cities: Vec<usize> = routes.iter().flat_map(|r| r[1..].iter().copied()).collect();
+            cities.sort();
+            assert_eq!(cities, (1..14).collect::<Vec<_>>(), "{}", solver.name());
+            assert!(routes.iter().all(|r| r[0] == 0));
+            assert!(total >= optimum - 1e-9, "{}", solver.name());
+        }
+    }
+    
+    #[test]
+    fn test_constraints_need_the_dp() {
+        let problem = Problem { precedence: vec![(3, 1)], listing: Listing::KBest(2), ..Problem::new(pseudo_random_matrix(5, false)) };
+        let solution = find(HELD_KARP).unwrap().solve(&problem, &Options::default()).unwrap();
+        assert_eq!(solution.alternatives.len(), 2);
+        assert!(solution.tour.iter().position(|&c| c == 3) < solution.tour.iter().position(|&c| c == 1));
+        
+        let heuristic = find("lk").unwrap();
+        assert!(!heuristic.supports_constraints());
+        assert!(heuristic.solve(&problem, &Options::default()).is_err());
+    }
+}
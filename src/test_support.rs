@@ -0,0 +1,54 @@
+use std::sync::Mutex;
+
+use crate::progress::{Progress, ProgressObserver};
+
+/// Euclidean distances between `n` deterministic points scattered over a 101 × 97 grid
+pub fn points_matrix(n: usize) -> Vec<Vec<f64>> {
+    let points: Vec<(f64, f64)> = (0..n)
+        .map(|i| (((i * 37) % 101) as f64, ((i * 59) % 97) as f64))
+        .collect();
+    euclidean(&points)
+}
+
+/// Euclidean distances between the corners of unit regular polygons with `sizes` corners,
+/// laid out a few units apart; odd polygons are odd cycles of short edges, which the
+/// blossom matching has to contract and later expand
+pub fn polygons_matrix(sizes: &[usize]) -> Vec<Vec<f64>> {
+    let mut points = Vec::new();
+    for (c, &corners) in sizes.iter().enumerate() {
+        let centre = ((c % 2) as f64 * 6.0 + c as f64 * 1.5, (c / 2) as f64 * 5.0);
+        for i in 0..corners {
+            let angle = i as f64 * std::f64::consts::TAU / corners as f64 + c as f64;
+            points.push((centre.0 + angle.cos(), centre.1 + angle.sin()));
+        }
+    }
+    euclidean(&points)
+}
+
+fn euclidean(points: &[(f64, f64)]) -> Vec<Vec<f64>> {
+    points.iter()
+        .map(|a| points.iter().map(|b| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()).collect())
+        .collect()
+}
+
+/// Deterministic integer distances between 1 and 53, asymmetric unless `symmetric`
+pub fn pseudo_random_matrix(n: usize, symmetric: bool) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n)
+            .map(|j| {
+                let (a, b) = if symmetric { (i.min(j), i.max(j)) } else { (i, j) };
+                if i == j { 0.0 } else { ((a * 37 + b * 91) % 53 + 1) as f64 }
+            })
+            .collect())
+        .collect()
+}
+
+/// Observer that keeps every progress report
+#[derive(Default)]
+pub struct ProgressRecorder(pub Mutex<Vec<Progress>>);
+
+impl ProgressObserver for ProgressRecorder {
+    fn update(&self, progress: &Progress) {
+        self.0.lock().unwrap().push(*progress);
+    }
+}
@@ -0,0 +1,682 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use crate::tsp_solver::{TieBreak, tour_cost};
+use crate::TSPError;
+
+/// Slack for floating-point comparisons against window bounds
+const EPSILON: f64 = 1e-9;
+
+/// Labels the exact DP may create before giving up (about 24 bytes each)
+const MAX_LABELS: usize = 10_000_000;
+
+/// Relocation passes of the heuristic's improvement phase
+const MAX_RELOCATE_PASSES: usize = 50;
+
+/// Service window and duration of one city; travel times equal distances
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeWindow {
+    /// Earliest time service may start (arriving earlier means waiting)
+    pub ready: f64,
+    /// Latest time service may start
+    pub due: f64,
+    pub service: f64,
+}
+
+impl TimeWindow {
+    /// No restriction and no service time
+    pub const OPEN: TimeWindow = TimeWindow {
+        ready: 0.0,
+        due: f64::INFINITY,
+        service: 0.0,
+    };
+}
+
+/// Times at one stop of a route
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stop {
+    pub city: usize,
+    pub arrival: f64,
+    /// Service start, after waiting for the window to open
+    pub start: f64,
+    pub departure: f64,
+}
+
+/// Stops of a closed route in visiting order, plus the return to the first city
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schedule {
+    pub stops: Vec<Stop>,
+    pub return_time: f64,
+}
+
+impl Schedule {
+    /// Follow `path` from its first city, leaving it at its ready time plus service
+    pub fn new(matrix: &[Vec<f64>], windows: &[TimeWindow], path: &[usize]) -> Self {
+        let mut stops: Vec<Stop> = Vec::with_capacity(path.len());
+        
+        for &city in path {
+            let arrival = match stops.last() {
+                Some(prev) => prev.departure + matrix[prev.city][city],
+                None => windows[city].ready,
+            };
+            let start = arrival.max(windows[city].ready);
+            stops.push(Stop {
+                city,
+                arrival,
+                start,
+                departure: start + windows[city].service,
+            });
+        }
+        
+        let return_time = match (stops.first(), stops.last()) {
+            (Some(first), Some(last)) => last.departure + matrix[last.city][first.city],
+            _ => 0.0,
+        };
+        
+        Self { stops, return_time }
+    }
+    
+    /// Stops whose service starts after the window closes, then the depot itself if the
+    /// route gets back too late; as (city, time, due)
+    pub fn violations(&self, windows: &[TimeWindow]) -> Vec<(usize, f64, f64)> {
+        let mut late: Vec<(usize, f64, f64)> = self.stops.iter()
+            .filter(|s| s.start > windows[s.city].due + EPSILON)
+            .map(|s| (s.city, s.start, windows[s.city].due))
+            .collect();
+        
+        if let Some(depot) = self.stops.first() {
+            if self.return_time > windows[depot.city].due + EPSILON {
+                late.push((depot.city, self.return_time, windows[depot.city].due));
+            }
+        }
+        
+        late
+    }
+}
+
+/// One partial route of the exact DP: cost and departure time at its last city
+#[derive(Clone, Copy)]
+struct Label {
+    cost: f64,
+    time: f64,
+    prev: u32,
+    city: u8,
+}
+
+/// TSP with time windows: shortest closed tour from the start city that begins service
+/// at every city inside its window
+pub struct TimeWindowSolver {
+    distance_matrix: Vec<Vec<f64>>,
+    windows: Vec<TimeWindow>,
+    n: usize,
+    start: usize,
+    shortest: Vec<Vec<f64>>, // All-pairs shortest travel times, for pruning
+    tie_break: TieBreak,
+}
+
+impl TimeWindowSolver {
+    pub fn new(distance_matrix: Vec<Vec<f64>>, windows: Vec<TimeWindow>) -> Self {
+        let n = distance_matrix.len();
+        let mut shortest = distance_matrix.clone();
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    let via = shortest[i][k] + shortest[k][j];
+                    if via < shortest[i][j] {
+                        shortest[i][j] = via;
+                    }
+                }
+            }
+        }
+        
+        Self {
+            distance_matrix,
+            windows,
+            n,
+            start: 0,
+            shortest,
+            tie_break: TieBreak::LoopOrder,
+        }
+    }
+    
+    /// Depart from (and return to) `start` instead of city 0
+    pub fn with_start(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+    
+    /// Which of several equally cheap tours the exact DP reports
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+    
+    /// Exact DP over (visited set, city), keeping every (cost, earliest departure) pair
+    /// that no other pair beats on both; states expand in (set, city) order, so equally
+    /// cheap tours resolve the same way on every run. Fails with the reasons when no tour fits
+    pub fn solve(&self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        if self.n > u8::MAX as usize || self.n >= usize::BITS as usize {
+            return Err(TSPError::SolverError(
+                format!("Too many cities for the time-window DP: {}", self.n)
+            ).into());
+        }
+        
+        println!("  • Time-window DP over {} cities", self.n);
+        
+        let depot = &self.windows[self.start];
+        let mut labels = vec![Label {
+            cost: 0.0,
+            time: depot.ready + depot.service,
+            prev: u32::MAX,
+            city: self.start as u8,
+        }];
+        let mut layer: BTreeMap<(usize, usize), Vec<u32>> = BTreeMap::new();
+        layer.insert((1 << self.start, self.start), vec![0]);
+        
+        for size in 2..=self.n {
+            let mut next_layer: BTreeMap<(usize, usize), Vec<u32>> = BTreeMap::new();
+            
+            for (&(mask, city), indices) in &layer {
+                for &index in indices {
+                    let label = labels[index as usize];
+                    
+                    for next in 0..self.n {
+                        if mask & (1 << next) != 0 {
+                            continue;
+                        }
+                        
+                        let new_mask = mask | (1 << next);
+                        let Some(time) = self.departure(label.time, city, next, |k| new_mask & (1 << k) == 0) else {
+                            continue;
+                        };
+                        let candidate = Label {
+                            cost: label.cost + self.distance_matrix[city][next],
+                            time,
+                            prev: index,
+                            city: next as u8,
+                        };
+                        
+                        let front = next_layer.entry((new_mask, next)).or_default();
+                        if front.iter().any(|&i| self.dominates(&labels, &labels[i as usize], &candidate)) {
+                            continue;
+                        }
+                        front.retain(|&i| !self.dominates(&labels, &candidate, &labels[i as usize]));
+                        
+                        if labels.len() >= MAX_LABELS {
+                            return Err(TSPError::SolverError(
+                                format!("Time windows too loose for the exact DP (over {} states); use cheapest-insertion", MAX_LABELS)
+                            ).into());
+                        }
+                        front.push(labels.len() as u32);
+                        labels.push(candidate);
+                    }
+                }
+            }
+            
+            next_layer.retain(|_, front| !front.is_empty());
+            if verbose {
+                let count: usize = next_layer.values().map(|f| f.len()).sum();
+                println!("    • {} cities visited: {} states, {} labels", size, next_layer.len(), count);
+            }
+            layer = next_layer;
+        }
+        
+        // Close the tour back at the depot; among equally cheap tours the tie-break
+        // decides, else the first one met
+        let mut best: Option<(f64, u32)> = None;
+        for (&(_, city), indices) in &layer {
+            for &index in indices {
+                let label = labels[index as usize];
+                let back = label.time + self.distance_matrix[city][self.start];
+                let cost = label.cost + self.distance_matrix[city][self.start];
+                if back > depot.due + EPSILON {
+                    continue;
+                }
+                let better = best.is_none_or(|(c, i)| {
+                    cost < c - EPSILON || (cost <= c + EPSILON && self.tie_break.compare(
+                        &self.distance_matrix, &route(&labels, &label), &route(&labels, &labels[i as usize])
+                    ) == Ordering::Less)
+                });
+                if better {
+                    best = Some((cost, index));
+                }
+            }
+        }
+        
+        println!("  • {} labels created", labels.len());
+        
+        let Some((cost, index)) = best else {
+            return Err(TSPError::SolverError(
+                format!("No tour meets every time window: {}", self.infeasibility_reasons().join("; "))
+            ).into());
+        };
+        
+        Ok((cost, route(&labels, &labels[index as usize])))
+    }
+    
+    /// Whether label `a` makes `b` redundant: no more expensive and no later, and when
+    /// both cost the same, not behind `b` under the tie-break
+    fn dominates(&self, labels: &[Label], a: &Label, b: &Label) -> bool {
+        if a.cost > b.cost + EPSILON || a.time > b.time + EPSILON {
+            return false;
+        }
+        if self.tie_break == TieBreak::LoopOrder || a.cost < b.cost - EPSILON {
+            return true;
+        }
+        self.tie_break.compare(&self.distance_matrix, &route(labels, a), &route(labels, b)) != Ordering::Greater
+    }
+    
+    /// Departure time from `next` when leaving `city` at `time`, or None if `next` or any
+    /// city still `unvisited` afterwards (or the return to the depot) can no longer be on time
+    fn departure(&self, time: f64, city: usize, next: usize, unvisited: impl Fn(usize) -> bool) -> Option<f64> {
+        let window = &self.windows[next];
+        let start = (time + self.distance_matrix[city][next]).max(window.ready);
+        if start > window.due + EPSILON {
+            return None;
+        }
+        
+        let departure = start + window.service;
+        let reachable = (0..self.n)
+            .filter(|&k| unvisited(k))
+            .chain(std::iter::once(self.start))
+            .all(|k| departure + self.shortest[next][k] <= self.windows[k].due + EPSILON);
+        reachable.then_some(departure)
+    }
+    
+    /// Best of a window-aware cheapest insertion and a time-aware nearest neighbour
+    /// (fewest late minutes first, then distance), repaired by moving late cities and
+    /// improved by relocations that keep every window met. Cities that fit nowhere stay
+    /// late and show up as violations in the schedule.
+    pub fn solve_heuristic(&self, verbose: bool) -> Result<(f64, Vec<usize>)> {
+        let candidates = [
+            ("insertion", self.insertion()),
+            ("nearest neighbour", self.time_aware_neighbour()),
+        ];
+        for (name, path) in &candidates {
+            println!("  • {} tour: {:.1}, lateness {:.1}",
+                    name, tour_cost(&self.distance_matrix, path), self.lateness(path));
+        }
+        
+        let (_, mut path) = candidates.into_iter()
+            .min_by(|(_, a), (_, b)| self.lateness(a).total_cmp(&self.lateness(b))
+                .then(tour_cost(&self.distance_matrix, a).total_cmp(&tour_cost(&self.distance_matrix, b))))
+            .unwrap();
+        
+        if self.lateness(&path) > EPSILON {
+            let moves = self.repair(&mut path);
+            println!("  • {} repair moves, lateness now {:.1}", moves, self.lateness(&path));
+        }
+        
+        if self.lateness(&path) <= EPSILON {
+            let before = tour_cost(&self.distance_matrix, &path);
+            let moves = self.relocate(&mut path);
+            if verbose {
+                println!("    • {} relocation moves: {:.1} -> {:.1}",
+                        moves, before, tour_cost(&self.distance_matrix, &path));
+            }
+        }
+        
+        Ok((tour_cost(&self.distance_matrix, &path), path))
+    }
+    
+    /// Cheapest insertion restricted to positions where every window stays met; cities
+    /// that fit nowhere are added at the end where they add the least distance
+    fn insertion(&self) -> Vec<usize> {
+        let mut path = vec![self.start];
+        let mut unrouted: Vec<usize> = (0..self.n).filter(|&c| c != self.start).collect();
+        
+        loop {
+            let (departures, latest) = self.slack(&path);
+            let mut best: Option<(f64, usize, usize)> = None; // (added distance, unrouted index, position)
+            
+            for (u, &city) in unrouted.iter().enumerate() {
+                for pos in 1..=path.len() {
+                    if !self.fits(&path, &departures, &latest, city, pos) {
+                        continue;
+                    }
+                    let added = self.added_distance(&path, city, pos);
+                    if best.is_none_or(|(d, _, _)| added < d) {
+                        best = Some((added, u, pos));
+                    }
+                }
+            }
+            
+            match best {
+                Some((_, u, pos)) => path.insert(pos, unrouted.swap_remove(u)),
+                None => break,
+            }
+        }
+        
+        // Leftovers, tightest deadline first
+        unrouted.sort_by(|&a, &b| self.windows[a].due.total_cmp(&self.windows[b].due));
+        for city in unrouted {
+            let pos = (1..=path.len())
+                .min_by(|&a, &b| self.added_distance(&path, city, a).total_cmp(&self.added_distance(&path, city, b)))
+                .unwrap();
+            path.insert(pos, city);
+        }
+        
+        path
+    }
+    
+    /// Nearest neighbour on travel plus waiting time, preferring cities after which every
+    /// other window can still be reached; when no city can be reached in time, the one
+    /// with the earliest deadline goes next
+    fn time_aware_neighbour(&self) -> Vec<usize> {
+        let mut path = vec![self.start];
+        let mut visited = vec![false; self.n];
+        visited[self.start] = true;
+        let depot = &self.windows[self.start];
+        let mut time = depot.ready + depot.service;
+        
+        for _ in 1..self.n {
+            let current = *path.last().unwrap();
+            let score = |next: usize| (time + self.distance_matrix[current][next]).max(self.windows[next].ready);
+            let reachable = |next: usize| {
+                time + self.distance_matrix[current][next] <= self.windows[next].due + EPSILON
+            };
+            // Going to `next` still leaves every other open window reachable
+            let safe = |next: usize| self.departure(time, current, next, |k| !visited[k] && k != next).is_some();
+            
+            let next = (0..self.n)
+                .filter(|&c| !visited[c] && safe(c))
+                .min_by(|&a, &b| score(a).total_cmp(&score(b)))
+                .or_else(|| (0..self.n)
+                    .filter(|&c| !visited[c] && reachable(c))
+                    .min_by(|&a, &b| score(a).total_cmp(&score(b))))
+                .or_else(|| (0..self.n)
+                    .filter(|&c| !visited[c])
+                    .min_by(|&a, &b| self.windows[a].due.total_cmp(&self.windows[b].due)))
+                .unwrap();
+            
+            let arrival = time + self.distance_matrix[current][next];
+            time = arrival.max(self.windows[next].ready) + self.windows[next].service;
+            visited[next] = true;
+            path.push(next);
+        }
+        
+        path
+    }
+    
+    /// Total time by which service starts (and the return) miss their windows
+    fn lateness(&self, path: &[usize]) -> f64 {
+        Schedule::new(&self.distance_matrix, &self.windows, path)
+            .violations(&self.windows)
+            .iter()
+            .fold(0.0, |total, &(_, time, due)| total + time - due)
+    }
+    
+    /// Move single cities wherever total lateness drops most, until it stops dropping;
+    /// returns the move count
+    fn repair(&self, path: &mut Vec<usize>) -> usize {
+        let mut moves = 0;
+        let mut current = self.lateness(path);
+        
+        for _ in 0..MAX_RELOCATE_PASSES {
+            let mut improved = false;
+            
+            for from in 1..path.len() {
+                let mut rest = path.clone();
+                let city = rest.remove(from);
+                
+                let best = (1..=rest.len())
+                    .filter(|&pos| pos != from)
+                    .map(|pos| {
+                        let mut candidate = rest.clone();
+                        candidate.insert(pos, city);
+                        (self.lateness(&candidate), candidate)
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                
+                if let Some((lateness, candidate)) = best {
+                    if lateness < current - EPSILON {
+                        *path = candidate;
+                        current = lateness;
+                        moves += 1;
+                        improved = true;
+                    }
+                }
+            }
+            
+            if !improved || current <= EPSILON {
+                break;
+            }
+        }
+        
+        moves
+    }
+    
+    /// Move single cities to cheaper feasible positions until none helps; returns the move count
+    fn relocate(&self, path: &mut Vec<usize>) -> usize {
+        let mut moves = 0;
+        
+        for _ in 0..MAX_RELOCATE_PASSES {
+            let mut improved = false;
+            
+            for from in 1..path.len() {
+                let city = path[from];
+                let saving = self.added_distance_without(path, from);
+                
+                let mut rest = path.clone();
+                rest.remove(from);
+                let (departures, latest) = self.slack(&rest);
+                
+                let best = (1..=rest.len())
+                    .filter(|&pos| pos != from && self.fits(&rest, &departures, &latest, city, pos))
+                    .map(|pos| (self.added_distance(&rest, city, pos), pos))
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                
+                if let Some((added, pos)) = best {
+                    if added < saving - EPSILON {
+                        rest.insert(pos, city);
+                        *path = rest;
+                        moves += 1;
+                        improved = true;
+                    }
+                }
+            }
+            
+            if !improved {
+                break;
+            }
+        }
+        
+        moves
+    }
+    
+    /// Departure time at every stop, and the latest service start at every stop (and at
+    /// the return, index len) that keeps the rest of the route on time
+    fn slack(&self, path: &[usize]) -> (Vec<f64>, Vec<f64>) {
+        let schedule = Schedule::new(&self.distance_matrix, &self.windows, path);
+        let departures: Vec<f64> = schedule.stops.iter().map(|s| s.departure).collect();
+        
+        let mut latest = vec![0.0; path.len() + 1];
+        latest[path.len()] = self.windows[self.start].due;
+        for i in (0..path.len()).rev() {
+            let next = path.get(i + 1).copied().unwrap_or(self.start);
+            let city = path[i];
+            latest[i] = self.windows[city].due
+                .min(latest[i + 1] - self.distance_matrix[city][next] - self.windows[city].service);
+        }
+        
+        (departures, latest)
+    }
+    
+    /// Whether `city` can go before position `pos` (pos == len means before the return)
+    /// without breaking its own window or delaying later stops past their latest start
+    fn fits(&self, path: &[usize], departures: &[f64], latest: &[f64], city: usize, pos: usize) -> bool {
+        let prev = path[pos - 1];
+        let next = path.get(pos).copied().unwrap_or(self.start);
+        let window = &self.windows[city];
+        
+        let start = (departures[pos - 1] + self.distance_matrix[prev][city]).max(window.ready);
+        if start > window.due + EPSILON {
+            return false;
+        }
+        
+        let arrival = start + window.service + self.distance_matrix[city][next];
+        let next_start = if pos < path.len() { arrival.max(self.windows[next].ready) } else { arrival };
+        next_start <= latest[pos] + EPSILON
+    }
+    
+    /// Extra distance of putting `city` before position `pos`
+    fn added_distance(&self, path: &[usize], city: usize, pos: usize) -> f64 {
+        let d = &self.distance_matrix;
+        let prev = path[pos - 1];
+        let next = path.get(pos).copied().unwrap_or(path[0]);
+        d[prev][city] + d[city][next] - d[prev][next]
+    }
+    
+    /// Distance saved by taking out the city at position `pos`
+    fn added_distance_without(&self, path: &[usize], pos: usize) -> f64 {
+        let mut rest = path.to_vec();
+        let city = rest.remove(pos);
+        self.added_distance(&rest, city, pos)
+    }
+    
+    /// Simple explanations for an infeasible instance
+    fn infeasibility_reasons(&self) -> Vec<String> {
+        let depot = &self.windows[self.start];
+        let leave = depot.ready + depot.service;
+        let mut reasons = Vec::new();
+        
+        for (city, window) in self.windows.iter().enumerate() {
+            if city == self.start {
+                continue;
+            }
+            
+            let earliest = (leave + self.shortest[self.start][city]).max(window.ready);
+            let back = earliest + window.service + self.shortest[city][self.start];
+            if earliest > window.due + EPSILON {
+                reasons.push(format!("city {} cannot be reached before its window closes at {} (earliest {})",
+                                    city, window.due, earliest));
+            } else if back > depot.due + EPSILON {
+                reasons.push(format!("serving city {} ends too late to return to city {} by {} (earliest {})",
+                                    city, self.start, depot.due, back));
+            }
+        }
+        
+        if reasons.is_empty() {
+            reasons.push("no visiting order satisfies every window at once".to_string());
+        }
+        reasons
+    }
+}
+
+/// `a` is at least as cheap and as early as `b`
+/// Cities of the partial route ending at `label`, from the start city
+fn route(labels: &[Label], label: &Label) -> Vec<usize> {
+    let mut path = vec![label.city as usize];
+    let mut index = label.prev;
+    while index != u32::MAX {
+        path.push(labels[index as usize].city as usize);
+        index = labels[index as usize].prev;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::points_matrix;
+    use crate::tsp_solver::TSPSolver;
+    
+    /// Cheapest feasible tour by trying every order of cities 1..n
+    fn brute_force(matrix: &[Vec<f64>], windows: &[TimeWindow]) -> Option<f64> {
+        fn permute(rest: &mut Vec<usize>, path: &mut Vec<usize>, visit: &mut dyn FnMut(&[usize])) {
+            if rest.is_empty() {
+                visit(path);
+                return;
+            }
+            for i in 0..rest.len() {
+                let city = rest.remove(i);
+                path.push(city);
+                permute(rest, path, visit);
+                path.pop();
+                rest.insert(i, city);
+            }
+        }
+        
+        let mut best: Option<f64> = None;
+        permute(&mut (1..matrix.len()).collect(), &mut vec![0], &mut |path| {
+            if Schedule::new(matrix, windows, path).violations(windows).is_empty() {
+                let cost = tour_cost(matrix, path);
+                if best.is_none_or(|b| cost < b) {
+                    best = Some(cost);
+                }
+            }
+        });
+        best
+    }
+    
+    #[test]
+    fn test_exact_matches_brute_force() {
+        let matrix = points_matrix(8);
+        let windows: Vec<TimeWindow> = (0..8)
+            .map(|i| match i {
+                0 => TimeWindow::OPEN,
+                _ => {
+                    let ready = ((i * 3) % 7) as f64 * 60.0;
+                    TimeWindow { ready, due: ready + 120.0, service: 5.0 }
+                }
+            })
+            .collect();
+        
+        let expected = brute_force(&matrix, &windows).expect("instance is feasible");
+        let (unconstrained, _) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        assert!(expected > unconstrained + 1e-9, "windows should change the optimum");
+        let (cost, path) = TimeWindowSolver::new(matrix.clone(), windows.clone()).solve(false).unwrap();
+        assert!((cost - expected).abs() < 1e-9, "{} vs {}", cost, expected);
+        assert!(Schedule::new(&matrix, &windows, &path).violations(&windows).is_empty());
+        
+        let (heuristic, path) = TimeWindowSolver::new(matrix.clone(), windows.clone()).solve_heuristic(false).unwrap();
+        assert!(heuristic >= expected - 1e-9);
+        assert!(Schedule::new(&matrix, &windows, &path).violations(&windows).is_empty());
+    }
+    
+    #[test]
+    fn test_infeasible_window_is_reported() {
+        let matrix = vec![
+            vec![0.0, 10.0, 10.0],
+            vec![10.0, 0.0, 5.0],
+            vec![10.0, 5.0, 0.0],
+        ];
+        let mut windows = vec![TimeWindow::OPEN; 3];
+        windows[2] = TimeWindow { ready: 0.0, due: 4.0, service: 0.0 };
+        
+        let err = TimeWindowSolver::new(matrix.clone(), windows.clone()).solve(false).unwrap_err();
+        assert!(err.to_string().contains("city 2 cannot be reached"), "{}", err);
+        
+        // The heuristic still returns a tour, with the late stop as a violation
+        let (_, path) = TimeWindowSolver::new(matrix.clone(), windows.clone()).solve_heuristic(false).unwrap();
+        let late = Schedule::new(&matrix, &windows, &path).violations(&windows);
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].0, 2);
+    }
+    
+    #[test]
+    fn test_ties_follow_the_policy() {
+        // Rectangle 2 × 1: both directions around it cost 6
+        let points = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)];
+        let matrix: Vec<Vec<f64>> = points.iter()
+            .map(|a: &(f64, f64)| points.iter().map(|b| (a.0 - b.0).hypot(a.1 - b.1)).collect())
+            .collect();
+        let windows = vec![TimeWindow::OPEN; 4];
+        let solve = |tie_break: TieBreak| {
+            TimeWindowSolver::new(matrix.clone(), windows.clone()).with_tie_break(tie_break).solve(false).unwrap()
+        };
+        
+        let (cost, first) = solve(TieBreak::LoopOrder);
+        assert!((cost - 6.0).abs() < 1e-9);
+        for _ in 0..5 {
+            assert_eq!(solve(TieBreak::LoopOrder).1, first);
+        }
+        
+        assert_eq!(solve(TieBreak::Lexicographic(vec![0, 1, 2, 3])).1, vec![0, 1, 2, 3]);
+        assert_eq!(solve(TieBreak::Lexicographic(vec![0, 3, 2, 1])).1, vec![0, 3, 2, 1]);
+        assert_eq!(solve(TieBreak::ShortestFirstLeg(vec![0, 1, 2, 3])).1, vec![0, 3, 2, 1]);
+    }
+}
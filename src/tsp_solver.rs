@@ -1,11 +1,171 @@
+use anyhow::{Result, anyhow};
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use anyhow::Result;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::cancel::CancelToken;
+use crate::progress::{Progress, ProgressObserver};
+use crate::TSPError;
+
+/// Parent sentinel for states without a successor (the full mask)
+const NO_PARENT: u8 = u8::MAX;
+
+/// Number of masks of one layer handed to the worker threads at a time
+const LAYER_BATCH: usize = 1 << 14;
+
+/// Masks between two cancellation checks and progress reports in the serial DPs
+const CANCEL_CHECK: usize = 1 << 10;
+
+/// k-best DP tables: costs and (next city, rank) parents, `k` consecutive entries per state
+type KBestTables = (Vec<f64>, Vec<(u8, u8)>);
+
+/// Shape of the route being optimised
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Route {
+    /// Closed tour that returns to the start city
+    Tour,
+    /// Hamiltonian path from the start city, ending at `end` or wherever is cheapest
+    Path { end: Option<usize> },
+    /// Hamiltonian path with both endpoints free
+    FreePath,
+}
+
+impl Route {
+    pub fn is_closed(&self) -> bool {
+        *self == Route::Tour
+    }
+}
+
+/// What the DP optimises over the edges of the route
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TourObjective {
+    /// Shortest total distance
+    MinTotal,
+    /// Shortest longest edge (bottleneck TSP)
+    MinBottleneck,
+    /// Longest total distance (max TSP)
+    MaxTotal,
+}
+
+impl TourObjective {
+    /// Value of a state with no feasible completion
+    fn worst(self) -> f64 {
+        match self {
+            TourObjective::MaxTotal => f64::NEG_INFINITY,
+            _ => f64::INFINITY,
+        }
+    }
+    
+    /// Cost of taking `edge` and then a completion costing `rest`
+    fn combine(self, edge: f64, rest: f64) -> f64 {
+        match self {
+            TourObjective::MinBottleneck => edge.max(rest),
+            _ => edge + rest,
+        }
+    }
+    
+    fn is_better(self, cost: f64, best: f64) -> bool {
+        match self {
+            TourObjective::MaxTotal => cost > best,
+            _ => cost < best,
+        }
+    }
+    
+    /// Best-first ordering of two costs
+    fn order(self, a: f64, b: f64) -> Ordering {
+        match self {
+            TourObjective::MaxTotal => b.total_cmp(&a),
+            _ => a.total_cmp(&b),
+        }
+    }
+}
+
+/// Which of several equally good routes the DP reports
+#[derive(Clone, Debug, PartialEq)]
+pub enum TieBreak {
+    /// Whichever successor the DP loop meets first (lowest city index)
+    LoopOrder,
+    /// Lowest-ranked city first at every step; `ranks[c]` is the rank of city c
+    Lexicographic(Vec<usize>),
+    /// Shortest first leg, then lowest-ranked city first like `Lexicographic`
+    ShortestFirstLeg(Vec<usize>),
+}
+
+impl TieBreak {
+    /// Order of two equally good routes (or route prefixes) from the same city; `Less`
+    /// prefers `a`, and `LoopOrder` has no preference
+    pub fn compare(&self, matrix: &[Vec<f64>], a: &[usize], b: &[usize]) -> Ordering {
+        let leg = |route: &[usize]| route.get(1).map_or(0.0, |&next| matrix[route[0]][next]);
+        match self {
+            TieBreak::LoopOrder => Ordering::Equal,
+            TieBreak::Lexicographic(ranks) => a.iter().map(|&c| ranks[c]).cmp(b.iter().map(|&c| ranks[c])),
+            TieBreak::ShortestFirstLeg(ranks) => leg(a).total_cmp(&leg(b))
+                .then_with(|| a.iter().map(|&c| ranks[c]).cmp(b.iter().map(|&c| ranks[c]))),
+        }
+    }
+    
+    fn map_ranks(&self, map: impl Fn(&[usize]) -> Vec<usize>) -> TieBreak {
+        match self {
+            TieBreak::LoopOrder => TieBreak::LoopOrder,
+            TieBreak::Lexicographic(ranks) => TieBreak::Lexicographic(map(ranks)),
+            TieBreak::ShortestFirstLeg(ranks) => TieBreak::ShortestFirstLeg(map(ranks)),
+        }
+    }
+}
+
+/// Results whose routes can be mapped back to the cities of another solver
+trait Restore {
+    fn restore(self, city: impl Fn(usize) -> usize) -> Self;
+}
+
+impl Restore for (f64, Vec<usize>) {
+    fn restore(self, city: impl Fn(usize) -> usize) -> Self {
+        (self.0, self.1.into_iter().map(city).collect())
+    }
+}
+
+impl Restore for Vec<(f64, Vec<usize>)> {
+    fn restore(self, city: impl Fn(usize) -> usize) -> Self {
+        self.into_iter().map(|route| route.restore(&city)).collect()
+    }
+}
+
+impl Restore for CoOptimal {
+    fn restore(mut self, city: impl Fn(usize) -> usize) -> Self {
+        self.routes = self.routes.into_iter().map(|route| route.into_iter().map(&city).collect()).collect();
+        self
+    }
+}
+
+/// Every optimal route (up to a cap, in tie-break order) and how many there are
+#[derive(Clone, Debug)]
+pub struct CoOptimal {
+    pub cost: f64,
+    pub count: u64,
+    pub routes: Vec<Vec<usize>>,
+}
 
 pub struct TSPSolver {
     distance_matrix: Vec<Vec<f64>>,
     n: usize,
-    memo: HashMap<(usize, usize), f64>, // (mask, current_city) -> min_cost
-    parent: HashMap<(usize, usize), usize>, // Path reconstruction
+    start: usize,
+    route: Route,
+    objective: TourObjective,
+    precedence: Vec<(usize, usize)>, // (before, after) pairs
+    clusters: Vec<usize>, // Cluster id per city (empty: every city is its own cluster)
+    tie_break: TieBreak,
+    dummy_start: bool, // City 0 is the zero-distance start of a free path
+    fixed_start: bool, // Keep the start even when another member of its cluster would do
+    cancel: CancelToken,
+    progress: Option<Arc<dyn ProgressObserver>>,
+    bits: Vec<usize>, // Per city: mask bit of its cluster (0 for the start's cluster)
+    mask_width: usize, // Number of clusters besides the start's
+    required: Vec<usize>, // Per city: mask of clusters that must be visited before it
+    cost: Vec<f64>, // [mask * (n - 1) + (city - 1)] -> min cost to finish the tour
+    parent: Vec<u8>, // Path reconstruction: best next city per state
+    states: u64, // States computed by the last finished DP
+    greedy: Option<f64>, // Greedy route cost, the best one known until the DP is done
 }
 
 impl TSPSolver {
@@ -14,11 +174,77 @@ impl TSPSolver {
         Self {
             distance_matrix,
             n,
-            memo: HashMap::new(),
-            parent: HashMap::new(),
+            start: 0,
+            route: Route::Tour,
+            objective: TourObjective::MinTotal,
+            precedence: Vec::new(),
+            clusters: Vec::new(),
+            tie_break: TieBreak::LoopOrder,
+            dummy_start: false,
+            fixed_start: false,
+            cancel: CancelToken::new(),
+            progress: None,
+            bits: Vec::new(),
+            mask_width: 0,
+            required: Vec::new(),
+            cost: Vec::new(),
+            parent: Vec::new(),
+            states: 0,
+            greedy: None,
         }
     }
     
+    /// Optimise an open path instead of a closed tour
+    pub fn with_route(mut self, route: Route) -> Self {
+        self.route = route;
+        self
+    }
+    
+    /// Start the route at `start` instead of city 0; closed tours are reported from `start`
+    pub fn with_start(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+    
+    /// Optimise the bottleneck edge or the maximum total instead of the total distance
+    pub fn with_objective(mut self, objective: TourObjective) -> Self {
+        self.objective = objective;
+        self
+    }
+    
+    /// Require `before` to be visited earlier than `after` for every `(before, after)` pair
+    pub fn with_precedence(mut self, precedence: Vec<(usize, usize)>) -> Self {
+        self.precedence = precedence;
+        self
+    }
+    
+    /// Generalized TSP: visit exactly one city of every cluster (`clusters[c]` is the
+    /// cluster id of city c). Paths begin at the start city; closed tours may visit
+    /// another member of its cluster instead, and then begin there.
+    pub fn with_clusters(mut self, clusters: Vec<usize>) -> Self {
+        self.clusters = clusters;
+        self
+    }
+    
+    /// Choose between equally good routes by city rank or first leg instead of loop order
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+    
+    /// Stop the DP with `TSPError::Cancelled` once `cancel` fires
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+    
+    /// Report states computed, current layer, best cost known (a greedy route's until the
+    /// optimum is) and elapsed time to `observer` while solving
+    pub fn with_progress(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.progress = Some(observer);
+        self
+    }
+    
     pub fn solve(&mut self, verbose: bool) -> Result<(f64, Vec<usize>)> {
         if self.n == 0 {
             return Ok((0.0, vec![]));
@@ -28,92 +254,1046 @@ impl TSPSolver {
             return Ok((0.0, vec![0]));
         }
         
+        if let Some(results) = self.per_start_member(|solver| solver.solve(verbose)) {
+            return Ok(self.best_of(results?));
+        }
+        
+        if let Some(mut reduced) = self.reduced() {
+            let (cost, path) = reduced.solve(verbose)?;
+            self.states = reduced.states;
+            return Ok((cost, self.restore(path)));
+        }
+        
+        self.prepare_masks()?;
+        if self.mask_width == 0 {
+            return Ok((0.0, vec![0]));
+        }
         println!("  • Initializing DP table for {} cities", self.n);
+        self.allocate_tables()?;
         
         // Mulai dari city 0
-        let start_mask = 1;
+        let started = Instant::now();
+        self.dp(started)?;
+        let (cost, path) = self.reconstruct_path()?;
+        self.finish_progress(started, cost, verbose);
+        Ok((cost, path))
+    }
+    
+    /// Same DP as `solve`, but every subset-size layer is split across `threads` workers.
+    /// `threads == 0` uses all available cores; the result is identical to `solve`.
+    pub fn solve_parallel(&mut self, verbose: bool, threads: usize) -> Result<(f64, Vec<usize>)> {
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, |t| t.get()),
+            t => t,
+        };
+        
+        if self.n <= 1 || threads == 1 {
+            return self.solve(verbose);
+        }
+        
+        if let Some(results) = self.per_start_member(|solver| solver.solve_parallel(verbose, threads)) {
+            return Ok(self.best_of(results?));
+        }
         
-        let min_cost = self.dp(start_mask, 0, verbose)?;
-        let path = self.reconstruct_path(start_mask, 0)?;
+        if let Some(mut reduced) = self.reduced() {
+            let (cost, path) = reduced.solve_parallel(verbose, threads)?;
+            self.states = reduced.states;
+            return Ok((cost, self.restore(path)));
+        }
+        
+        self.prepare_masks()?;
+        if self.mask_width == 0 {
+            return Ok((0.0, vec![0]));
+        }
+        println!("  • Initializing DP table for {} cities ({} threads)", self.n, threads);
+        self.allocate_tables()?;
         
-        Ok((min_cost, path))
+        let started = Instant::now();
+        self.dp_parallel(threads, started)?;
+        let (cost, path) = self.reconstruct_path()?;
+        self.finish_progress(started, cost, verbose);
+        Ok((cost, path))
     }
     
-    /// Dynamic Programming
-    /// Mask: bitmask visited city
-    fn dp(&mut self, mask: usize, current: usize, verbose: bool) -> Result<f64> {
-        // Base case: Semua city visited
-        if mask == (1 << self.n) - 1 {
-            return Ok(self.distance_matrix[current][0]);
+    /// The `k` best distinct routes, best first (fewer if fewer exist). Every state of the
+    /// DP keeps its `k` best completions instead of one; on symmetric matrices a closed
+    /// tour and its reverse count once, so `2k` directed routes are ranked.
+    pub fn solve_k_best(&mut self, k: usize, verbose: bool) -> Result<Vec<(f64, Vec<usize>)>> {
+        let reversible = self.is_reversible();
+        // Ranks are stored as u8, and reversible routes come in pairs of directions
+        let (wanted, most) = if reversible { (2 * k, u8::MAX as usize / 2) } else { (k, u8::MAX as usize) };
+        if k == 0 || wanted > u8::MAX as usize {
+            return Err(anyhow!("Number of tours must be between 1 and {}", most));
         }
         
-        // Cek memoization
-        if let Some(&cached_result) = self.memo.get(&(mask, current)) {
-            return Ok(cached_result);
+        let mut routes = self.k_best_directed(wanted, verbose)?;
+        if reversible {
+            let mut distinct: Vec<(f64, Vec<usize>)> = Vec::with_capacity(k);
+            for (cost, path) in routes {
+                if !distinct.iter().any(|(_, p)| is_reverse(p, &path, self.route)) {
+                    distinct.push((cost, path));
+                }
+            }
+            routes = distinct;
         }
+        routes.truncate(k);
+        Ok(routes)
+    }
+    
+    /// Every optimal route up to `cap` of them, in tie-break order, and their number. On
+    /// symmetric matrices a closed tour and its reverse count once.
+    pub fn solve_co_optimal(&mut self, cap: usize, verbose: bool) -> Result<CoOptimal> {
+        if cap == 0 {
+            return Err(anyhow!("Number of tours must be at least 1"));
+        }
+        let reversible = self.is_reversible();
+        let wanted = if reversible { cap.saturating_mul(2) } else { cap };
         
-        let mut min_cost = f64::INFINITY;
-        let mut best_next = 0;
+        let mut optimal = self.co_optimal_directed(wanted, verbose)?;
+        if reversible {
+            let mut distinct: Vec<Vec<usize>> = Vec::with_capacity(cap);
+            for path in optimal.routes {
+                if !distinct.iter().any(|p| is_reverse(p, &path, self.route)) {
+                    distinct.push(path);
+                }
+            }
+            // Palindromes (routes of one or two cities) are their own reverse
+            if !is_reverse(&distinct[0], &distinct[0], self.route) {
+                optimal.count /= 2;
+            }
+            optimal.routes = distinct;
+        }
+        optimal.routes.truncate(cap);
+        Ok(optimal)
+    }
+    
+    fn co_optimal_directed(&mut self, cap: usize, verbose: bool) -> Result<CoOptimal> {
+        if self.n <= 1 {
+            return Ok(CoOptimal { cost: 0.0, count: 1, routes: vec![(0..self.n).collect()] });
+        }
         
-        // Visit unvisited city
-        for next in 0..self.n {
-            if mask & (1 << next) == 0 { // City not visited
-                let new_mask = mask | (1 << next);
-                let cost = self.distance_matrix[current][next] + 
-                          self.dp(new_mask, next, verbose)?;
+        if let Some(results) = self.per_start_member(|solver| solver.co_optimal_directed(cap, verbose)) {
+            // Members reach the optimum with disjoint sets of routes
+            let results = results?;
+            let cost = results.iter().map(|r| r.cost).reduce(|a, b| if self.objective.is_better(b, a) { b } else { a }).unwrap();
+            let tight = |value: f64| (value - cost).abs() <= 1e-9 * cost.abs().max(1.0);
+            let optimal: Vec<CoOptimal> = results.into_iter().filter(|r| tight(r.cost)).collect();
+            let count = optimal.iter().fold(0u64, |total, r| total.saturating_add(r.count));
+            let routes = optimal.into_iter().flat_map(|r| r.routes).take(cap).collect();
+            return Ok(CoOptimal { cost, count, routes });
+        }
+        
+        if let Some(mut reduced) = self.reduced() {
+            let mut optimal = reduced.co_optimal_directed(cap, verbose)?;
+            self.states = reduced.states;
+            optimal.routes = optimal.routes.into_iter().map(|path| self.restore(path)).collect();
+            return Ok(optimal);
+        }
+        
+        self.prepare_masks()?;
+        if self.mask_width == 0 {
+            return Ok(CoOptimal { cost: 0.0, count: 1, routes: vec![vec![0]] });
+        }
+        println!("  • Initializing DP table for {} cities", self.n);
+        self.allocate_tables()?;
+        let started = Instant::now();
+        self.dp(started)?;
+        let (cost, _) = self.reconstruct_path()?;
+        self.finish_progress(started, cost, verbose);
+        
+        let count = self.count_optimal(0, 0, cost, &mut HashMap::new());
+        let mut routes = Vec::new();
+        self.collect_optimal(&mut vec![0], 0, cost, cap, &mut routes);
+        if verbose {
+            println!("    • {} optimal routes, {} listed", count, routes.len());
+        }
+        Ok(CoOptimal { cost, count, routes })
+    }
+    
+    /// Number of optimal completions of state (mask, current) worth `value` (saturating)
+    fn count_optimal(&self, mask: usize, current: usize, value: f64,
+                     memo: &mut HashMap<(usize, usize), u64>) -> u64 {
+        if mask == (1usize << self.mask_width) - 1 {
+            return 1;
+        }
+        if let Some(&count) = memo.get(&(mask, current)) {
+            return count;
+        }
+        let mut count = 0u64;
+        for next in self.tight_moves(mask, current, value) {
+            let next_mask = mask | self.bits[next];
+            let rest = self.cost[self.index(next_mask, next)];
+            count = count.saturating_add(self.count_optimal(next_mask, next, rest, memo));
+        }
+        memo.insert((mask, current), count);
+        count
+    }
+    
+    /// Depth-first listing of the optimal completions of `path`, in tie-break order
+    fn collect_optimal(&self, path: &mut Vec<usize>, mask: usize, value: f64,
+                       cap: usize, routes: &mut Vec<Vec<usize>>) {
+        if mask == (1usize << self.mask_width) - 1 {
+            routes.push(path.clone());
+            return;
+        }
+        for next in self.tight_moves(mask, path[path.len() - 1], value) {
+            if routes.len() >= cap {
+                return;
+            }
+            let next_mask = mask | self.bits[next];
+            path.push(next);
+            self.collect_optimal(path, next_mask, self.cost[self.index(next_mask, next)], cap, routes);
+            path.pop();
+        }
+    }
+    
+    /// Successors of state (mask, current) that keep a route of cost `value` optimal, in
+    /// tie-break order; mask 0 with city 0 is the start
+    fn tight_moves(&self, mask: usize, current: usize, value: f64) -> Vec<usize> {
+        let mut moves: Vec<(f64, usize, usize)> = self.tight_successors(mask, current, value)
+            .map(|next| {
+                let (leg, rank) = self.tie_key(mask, current, next);
+                (leg, rank, next)
+            })
+            .collect();
+        moves.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        moves.into_iter().map(|(_, _, next)| next).collect()
+    }
+    
+    fn tight_successors(&self, mask: usize, current: usize, value: f64) -> impl Iterator<Item = usize> + '_ {
+        (1..self.n).filter(move |&next| {
+            let bit = self.bits[next];
+            if bit == 0 || mask & bit != 0 || self.required[next] & !mask != 0 {
+                return false;
+            }
+            let cost = self.objective.combine(self.distance_matrix[current][next],
+                                              self.cost[self.index(mask | bit, next)]);
+            (cost - value).abs() <= 1e-9 * value.abs().max(1.0)
+        })
+    }
+    
+    /// (first leg, rank) sort key of moving from `current` to `next`; the leg is 0 except
+    /// at the first real edge of the route under `ShortestFirstLeg`
+    fn tie_key(&self, mask: usize, current: usize, next: usize) -> (f64, usize) {
+        let ranks = match &self.tie_break {
+            TieBreak::LoopOrder => return (0.0, next),
+            TieBreak::Lexicographic(ranks) => return (0.0, ranks[next]),
+            TieBreak::ShortestFirstLeg(ranks) => ranks,
+        };
+        let depth = mask.count_ones() as usize;
+        let first_leg = self.dummy_start as usize;
+        let leg = match depth.cmp(&first_leg) {
+            Ordering::Equal => self.distance_matrix[current][next],
+            // Leaving the free-path start: the first leg is the one out of `next`
+            Ordering::Less => {
+                let next_mask = mask | self.bits[next];
+                let value = self.cost[self.index(next_mask, next)];
+                self.tight_successors(next_mask, next, value)
+                    .map(|after| self.distance_matrix[next][after])
+                    .reduce(f64::min)
+                    .unwrap_or(0.0)
+            }
+            Ordering::Greater => 0.0,
+        };
+        (leg, ranks[next])
+    }
+    
+    /// Closed tours and free paths read the same backwards on symmetric matrices
+    fn is_reversible(&self) -> bool {
+        matches!(self.route, Route::Tour | Route::FreePath)
+            && self.precedence.is_empty()
+            && is_symmetric(&self.distance_matrix)
+    }
+    
+    fn k_best_directed(&mut self, k: usize, verbose: bool) -> Result<Vec<(f64, Vec<usize>)>> {
+        if self.n <= 1 {
+            return Ok(vec![(0.0, (0..self.n).collect())]);
+        }
+        
+        if let Some(results) = self.per_start_member(|solver| solver.k_best_directed(k, verbose)) {
+            let mut routes: Vec<(f64, Vec<usize>)> = results?.into_iter().flatten().collect();
+            routes.sort_by(|a, b| self.objective.order(a.0, b.0));
+            routes.truncate(k);
+            return Ok(routes);
+        }
+        
+        if let Some(mut reduced) = self.reduced() {
+            let routes = reduced.k_best_directed(k, verbose)?;
+            return Ok(routes.into_iter().map(|(cost, path)| (cost, self.restore(path))).collect());
+        }
+        
+        self.prepare_masks()?;
+        if self.mask_width == 0 {
+            return Ok(vec![(0.0, vec![0])]);
+        }
+        println!("  • Initializing k-best DP table for {} cities ({} routes per state)", self.n, k);
+        let started = Instant::now();
+        let (cost, parent) = self.k_best_dp(k, started)?;
+        
+        // Rank the first moves from city 0, then follow each chain of (next, rank) parents
+        let mut starts: Vec<(f64, usize, usize)> = Vec::new();
+        for first in (1..self.n).filter(|&c| self.bits[c] != 0 && self.required[c] == 0) {
+            let idx = self.index(self.bits[first], first) * k;
+            for rank in (0..k).filter(|&r| cost[idx + r].is_finite()) {
+                starts.push((self.objective.combine(self.distance_matrix[0][first], cost[idx + rank]), first, rank));
+            }
+        }
+        starts.sort_by(|a, b| self.objective.order(a.0, b.0));
+        starts.truncate(k);
+        
+        if starts.is_empty() {
+            return Err(TSPError::SolverError(
+                "No visiting order satisfies the precedence constraints".to_string()
+            ).into());
+        }
+        if verbose {
+            println!("    • Found {} routes", starts.len());
+        }
+        
+        Ok(starts.into_iter()
+            .map(|(total, first, rank)| {
+                let mut path = vec![0, first];
+                let (mut mask, mut city, mut rank) = (self.bits[first], first, rank);
+                loop {
+                    let (next, next_rank) = parent[self.index(mask, city) * k + rank];
+                    if next == NO_PARENT {
+                        break;
+                    }
+                    city = next as usize;
+                    rank = next_rank as usize;
+                    mask |= self.bits[city];
+                    path.push(city);
+                }
+                (total, path)
+            })
+            .collect())
+    }
+    
+    /// Top-k version of `dp`: `k` completion costs per state, best first, with the
+    /// (next city, rank in the next state) each one continues with
+    fn k_best_dp(&self, k: usize, started: Instant) -> Result<KBestTables> {
+        if self.n > u8::MAX as usize || self.mask_width >= usize::BITS as usize {
+            return Err(anyhow!("Too many cities for the DP table: {}", self.n));
+        }
+        let entries = (1usize << self.mask_width)
+            .checked_mul(self.width())
+            .and_then(|states| states.checked_mul(k))
+            .ok_or_else(|| anyhow!("DP table size overflows for {} cities", self.n))?;
+        
+        let mut cost = Vec::new();
+        let mut parent = Vec::new();
+        cost.try_reserve_exact(entries)
+            .and_then(|_| parent.try_reserve_exact(entries))
+            .map_err(|_| anyhow!("Not enough memory for a k-best DP table of {} entries", entries))?;
+        cost.resize(entries, self.objective.worst());
+        parent.resize(entries, (NO_PARENT, 0));
+        
+        let full = (1usize << self.mask_width) - 1;
+        let mut states = 0;
+        for (mask, layer, position) in self.layered_masks() {
+            if position % CANCEL_CHECK == 0 {
+                if self.cancel.is_cancelled() {
+                    return Err(cancelled());
+                }
+                self.report(states, layer, started);
+            }
+            for current in (1..self.n).filter(|&c| mask & self.bits[c] != 0) {
+                let idx = self.index(mask, current) * k;
+                states += 1;
+                
+                if mask == full {
+                    let (closing, _) = self.best_transition(mask, current);
+                    if closing.is_finite() {
+                        cost[idx] = closing;
+                    }
+                    continue;
+                }
+                
+                // Merge the sorted lists of the successors into the k slots of this state
+                let mut filled = 0;
+                for next in 1..self.n {
+                    let bit = self.bits[next];
+                    if bit == 0 || mask & bit != 0 || self.required[next] & !mask != 0 {
+                        continue;
+                    }
+                    let next_idx = self.index(mask | bit, next) * k;
+                    for rank in 0..k {
+                        let rest = cost[next_idx + rank];
+                        if !rest.is_finite() {
+                            break;
+                        }
+                        let total = self.objective.combine(self.distance_matrix[current][next], rest);
+                        if filled == k && !self.objective.is_better(total, cost[idx + k - 1]) {
+                            break; // The rest of this list is no better
+                        }
+                        
+                        let mut slot = filled.min(k - 1);
+                        while slot > 0 && self.objective.is_better(total, cost[idx + slot - 1]) {
+                            cost[idx + slot] = cost[idx + slot - 1];
+                            parent[idx + slot] = parent[idx + slot - 1];
+                            slot -= 1;
+                        }
+                        cost[idx + slot] = total;
+                        parent[idx + slot] = (next as u8, rank as u8);
+                        filled = (filled + 1).min(k);
+                    }
+                }
+            }
+        }
+        
+        Ok((cost, parent))
+    }
+    
+    /// Equivalent solver for a route that starts at a fixed city 0, unless this is one
+    fn reduced(&self) -> Option<TSPSolver> {
+        match self.route {
+            Route::FreePath => Some(self.free_path_solver()),
+            _ if self.start != 0 => Some(self.start_swapped_solver(self.start)),
+            _ => None,
+        }
+    }
+    
+    /// A closed generalized tour visits one member of the start's cluster, not necessarily
+    /// the start itself: run `solve` from every member (the start first) and return the
+    /// results with their cities restored. None when the start's cluster has one member.
+    fn per_start_member<T: Restore>(&mut self, mut solve: impl FnMut(&mut TSPSolver) -> Result<T>) -> Option<Result<Vec<T>>> {
+        if self.fixed_start || self.route != Route::Tour || self.clusters.len() != self.n || self.start >= self.n {
+            return None;
+        }
+        let cluster = self.clusters[self.start];
+        let others: Vec<usize> = (0..self.n).filter(|&c| c != self.start && self.clusters[c] == cluster).collect();
+        if others.is_empty() {
+            return None;
+        }
+        
+        self.states = 0;
+        let mut results = Vec::with_capacity(others.len() + 1);
+        for member in std::iter::once(self.start).chain(others) {
+            let mut solver = self.start_swapped_solver(member);
+            solver.fixed_start = true;
+            match solve(&mut solver) {
+                Ok(result) => results.push(result.restore(|c| swap_start(c, member))),
+                Err(error) => return Some(Err(error)),
+            }
+            self.states += solver.states;
+        }
+        Some(Ok(results))
+    }
+    
+    /// Best of several (cost, route) results, the first among equals
+    fn best_of(&self, results: Vec<(f64, Vec<usize>)>) -> (f64, Vec<usize>) {
+        results.into_iter()
+            .reduce(|best, next| if self.objective.is_better(next.0, best.0) { next } else { best })
+            .unwrap()
+    }
+    
+    /// Map a route of the `reduced` solver back to these cities
+    fn restore(&self, path: Vec<usize>) -> Vec<usize> {
+        match self.route {
+            Route::FreePath => path[1..].iter().map(|&c| c - 1).collect(),
+            _ => path.into_iter().map(|c| swap_start(c, self.start)).collect(),
+        }
+    }
+    
+    /// A path with free endpoints is a path from a new city 0 that is at distance 0
+    /// from every other city; the original cities move up by one index
+    fn free_path_solver(&self) -> TSPSolver {
+        let matrix = (0..=self.n)
+            .map(|i| (0..=self.n)
+                .map(|j| if i == 0 || j == 0 { 0.0 } else { self.distance_matrix[i - 1][j - 1] })
+                .collect())
+            .collect();
+        let precedence = self.precedence.iter().map(|&(a, b)| (a + 1, b + 1)).collect();
+        let clusters = match self.clusters.iter().max() {
+            Some(&last) => std::iter::once(last + 1).chain(self.clusters.iter().copied()).collect(),
+            None => Vec::new(),
+        };
+        let tie_break = self.tie_break.map_ranks(|ranks| std::iter::once(0).chain(ranks.iter().copied()).collect());
+        let mut solver = TSPSolver::new(matrix)
+            .with_route(Route::Path { end: None })
+            .with_objective(self.objective)
+            .with_precedence(precedence)
+            .with_clusters(clusters)
+            .with_tie_break(tie_break)
+            .with_cancel(self.cancel.clone());
+        solver.progress = self.progress.clone();
+        solver.dummy_start = true;
+        solver
+    }
+    
+    /// The DP always starts at city 0, so solve a copy where `start` and city 0 trade places
+    fn start_swapped_solver(&self, start: usize) -> TSPSolver {
+        let swap = |c| swap_start(c, start);
+        let matrix = (0..self.n)
+            .map(|i| (0..self.n).map(|j| self.distance_matrix[swap(i)][swap(j)]).collect())
+            .collect();
+        let route = match self.route {
+            Route::Path { end } => Route::Path { end: end.map(swap) },
+            route => route,
+        };
+        let precedence = self.precedence.iter().map(|&(a, b)| (swap(a), swap(b))).collect();
+        let clusters = (0..self.clusters.len()).map(|c| self.clusters[swap(c)]).collect();
+        let tie_break = self.tie_break.map_ranks(|ranks| (0..ranks.len()).map(|c| ranks[swap(c)]).collect());
+        let mut solver = TSPSolver::new(matrix)
+            .with_route(route)
+            .with_objective(self.objective)
+            .with_precedence(precedence)
+            .with_clusters(clusters)
+            .with_tie_break(tie_break);
+        solver.cancel = self.cancel.clone();
+        solver.progress = self.progress.clone();
+        solver
+    }
+    
+    /// Give every cluster a mask bit, and turn the precedence pairs into per-city masks
+    /// of required predecessors
+    fn prepare_masks(&mut self) -> Result<()> {
+        self.bits = vec![0; self.n];
+        if self.clusters.is_empty() {
+            for city in 1..self.n {
+                self.bits[city] = 1 << (city - 1);
+            }
+            self.mask_width = self.n - 1;
+        } else {
+            if self.clusters.len() != self.n {
+                return Err(anyhow!("Expected a cluster for each of the {} cities", self.n));
+            }
+            // Other members of the start's cluster are never visited (bit 0)
+            let mut ids: Vec<usize> = Vec::new();
+            for city in (1..self.n).filter(|&c| self.clusters[c] != self.clusters[0]) {
+                let index = ids.iter().position(|&id| id == self.clusters[city]).unwrap_or_else(|| {
+                    ids.push(self.clusters[city]);
+                    ids.len() - 1
+                });
+                if index >= usize::BITS as usize - 1 {
+                    return Err(anyhow!("Too many clusters for the DP table: {}", index + 1));
+                }
+                self.bits[city] = 1 << index;
+            }
+            self.mask_width = ids.len();
+        }
+        
+        if let TieBreak::Lexicographic(ranks) | TieBreak::ShortestFirstLeg(ranks) = &self.tie_break {
+            if ranks.len() != self.n {
+                return Err(anyhow!("Expected a tie-break rank for each of the {} cities", self.n));
+            }
+        }
+        
+        self.required = vec![0; self.n];
+        for &(before, after) in &self.precedence {
+            if before == after || before >= self.n || after >= self.n {
+                return Err(anyhow!("Invalid precedence pair ({}, {})", before, after));
+            }
+            if after == 0 {
+                return Err(TSPError::SolverError(
+                    "The start city cannot come after another city in a precedence pair".to_string()
+                ).into());
+            }
+            self.required[after] |= self.bits[before]; // The start city is always visited first
+        }
+        self.greedy = self.greedy_cost();
+        Ok(())
+    }
+    
+    /// Cost of the route that always takes the best allowed edge (the shortest, or the
+    /// longest for `MaxTotal`), keeping a fixed path end for last; None when the greedy
+    /// choices leave no way to finish
+    fn greedy_cost(&self) -> Option<f64> {
+        let full = (1usize << self.mask_width) - 1;
+        let end = match self.route {
+            Route::Path { end } => end,
+            _ => None,
+        };
+        let d = &self.distance_matrix;
+        let (mut mask, mut current, mut cost) = (0, 0, 0.0);
+        while mask != full {
+            let next = (1..self.n)
+                .filter(|&c| self.bits[c] != 0 && mask & self.bits[c] == 0 && self.required[c] & !mask == 0)
+                .filter(|&c| end != Some(c) || mask | self.bits[c] == full)
+                .reduce(|a, b| if self.objective.is_better(d[current][b], d[current][a]) { b } else { a })?;
+            cost = self.objective.combine(d[current][next], cost);
+            mask |= self.bits[next];
+            current = next;
+        }
+        let (closing, _) = self.best_transition(full, current);
+        closing.is_finite().then(|| self.objective.combine(closing, cost))
+    }
+    
+    /// Number of non-start cities, i.e. the table block of one mask
+    fn width(&self) -> usize {
+        self.n - 1
+    }
+    
+    /// Flat table index of state (mask, city); city 0 is the fixed start and has no slot
+    fn index(&self, mask: usize, city: usize) -> usize {
+        mask * self.width() + (city - 1)
+    }
+    
+    /// Allocate the flat cost and parent tables, failing cleanly instead of aborting
+    fn allocate_tables(&mut self) -> Result<()> {
+        if self.n > u8::MAX as usize || self.mask_width >= usize::BITS as usize {
+            return Err(anyhow!("Too many cities for the DP table: {}", self.n));
+        }
+        
+        let states = (1usize << self.mask_width)
+            .checked_mul(self.width())
+            .ok_or_else(|| anyhow!("DP table size overflows for {} cities", self.n))?;
+        
+        let mut cost = Vec::new();
+        let mut parent = Vec::new();
+        cost.try_reserve_exact(states)
+            .and_then(|_| parent.try_reserve_exact(states))
+            .map_err(|_| anyhow!("Not enough memory for a DP table of {} states", states))?;
+        cost.resize(states, f64::INFINITY);
+        parent.resize(states, NO_PARENT);
+        
+        self.cost = cost;
+        self.parent = parent;
+        Ok(())
+    }
+    
+    /// Dynamic Programming (bottom-up)
+    /// Mask: bitmask visited non-start city, bit j-1 is city j (one bit per cluster for
+    /// the generalized TSP)
+    /// cost[mask][current] = min cost to visit every city outside mask and return to 0
+    /// (or stop, for open paths)
+    fn dp(&mut self, started: Instant) -> Result<()> {
+        let mut states = 0;
+        for (mask, layer, position) in self.layered_masks() {
+            if position % CANCEL_CHECK == 0 {
+                if self.cancel.is_cancelled() {
+                    return Err(cancelled());
+                }
+                self.report(states, layer, started);
+            }
+            for current in 1..self.n {
+                if mask & self.bits[current] == 0 {
+                    continue;
+                }
                 
-                if cost < min_cost {
-                    min_cost = cost;
-                    best_next = next;
+                let (min_cost, best_next) = self.best_transition(mask, current);
+                let idx = self.index(mask, current);
+                self.cost[idx] = min_cost;
+                self.parent[idx] = best_next;
+                states += 1;
+            }
+        }
+        Ok(())
+    }
+    
+    /// (mask, layer, position in the layer) for every mask, one popcount layer at a time
+    /// from the full mask down; a state only depends on the layer above it
+    fn layered_masks(&self) -> impl Iterator<Item = (usize, usize, usize)> {
+        let width = self.mask_width;
+        (1..=width).rev().flat_map(move |layer| {
+            LayerMasks::new(width, layer).enumerate().map(move |(position, mask)| (mask, layer, position))
+        })
+    }
+    
+    /// Layer-by-layer DP: masks with the same popcount only depend on the layer above
+    fn dp_parallel(&mut self, threads: usize, started: Instant) -> Result<()> {
+        let width = self.width();
+        let mut states = 0;
+        
+        for layer in (1..=self.mask_width).rev() {
+            let mut masks = LayerMasks::new(self.mask_width, layer);
+            
+            loop {
+                let batch: Vec<usize> = masks.by_ref().take(LAYER_BATCH).collect();
+                if batch.is_empty() {
+                    break;
+                }
+                if self.cancel.is_cancelled() {
+                    return Err(cancelled());
                 }
+                self.report(states, layer, started);
+                
+                let (costs, parents) = self.solve_batch(&batch, threads);
+                
+                for (i, &mask) in batch.iter().enumerate() {
+                    let block = mask * width..(mask + 1) * width;
+                    self.cost[block.clone()].copy_from_slice(&costs[i * width..(i + 1) * width]);
+                    self.parent[block].copy_from_slice(&parents[i * width..(i + 1) * width]);
+                    states += (1..self.n).filter(|&c| mask & self.bits[c] != 0).count() as u64;
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Compute the table blocks of a batch of masks on scoped worker threads
+    fn solve_batch(&self, batch: &[usize], threads: usize) -> (Vec<f64>, Vec<u8>) {
+        let width = self.width();
+        let chunk_size = batch.len().div_ceil(threads);
+        
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = batch
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let mut costs = vec![f64::INFINITY; chunk.len() * width];
+                        let mut parents = vec![NO_PARENT; chunk.len() * width];
+                        
+                        for (i, &mask) in chunk.iter().enumerate() {
+                            for current in 1..self.n {
+                                if mask & self.bits[current] != 0 {
+                                    let (cost, next) = self.best_transition(mask, current);
+                                    costs[i * width + current - 1] = cost;
+                                    parents[i * width + current - 1] = next;
+                                }
+                            }
+                        }
+                        
+                        (costs, parents)
+                    })
+                })
+                .collect();
+            
+            let mut costs = Vec::with_capacity(batch.len() * width);
+            let mut parents = Vec::with_capacity(batch.len() * width);
+            for worker in workers {
+                let (c, p) = worker.join().expect("DP worker thread panicked");
+                costs.extend(c);
+                parents.extend(p);
             }
+            (costs, parents)
+        })
+    }
+    
+    /// Best successor of state (mask, current); returns (cost, next city)
+    fn best_transition(&self, mask: usize, current: usize) -> (f64, u8) {
+        let full = (1usize << self.mask_width) - 1;
+        
+        // Base case: Semua city visited
+        if mask == full {
+            let closing = match self.route {
+                Route::Tour => self.distance_matrix[current][0],
+                Route::Path { end: Some(end) } if end != current => self.objective.worst(),
+                _ => 0.0,
+            };
+            return (closing, NO_PARENT);
         }
         
-        // Memoize result
-        self.memo.insert((mask, current), min_cost);
-        self.parent.insert((mask, current), best_next);
+        let mut best_cost = self.objective.worst();
+        let mut best_next = NO_PARENT;
         
-        if verbose && self.count_bits(mask) <= 3 {
-            println!("    • DP({:0width$b}, {}) = {:.1}", 
-                    mask, current, min_cost, width = self.n);
+        // Visit unvisited city
+        for next in 1..self.n {
+            // City (cluster) not visited, and all of its predecessors are
+            let bit = self.bits[next];
+            if bit != 0 && mask & bit == 0 && self.required[next] & !mask == 0 {
+                let new_mask = mask | bit;
+                let cost = self.objective.combine(self.distance_matrix[current][next],
+                                                  self.cost[self.index(new_mask, next)]);
+                
+                if self.objective.is_better(cost, best_cost) {
+                    best_cost = cost;
+                    best_next = next as u8;
+                }
+            }
         }
         
-        Ok(min_cost)
+        (best_cost, best_next)
     }
     
-    /// Reconstruct optimal path
-    fn reconstruct_path(&self, start_mask: usize, start_city: usize) -> Result<Vec<usize>> {
-        let mut path = vec![start_city];
-        let mut current_mask = start_mask;
-        let mut current_city = start_city;
+    /// Reconstruct optimal path starting from city 0
+    fn reconstruct_path(&self) -> Result<(f64, Vec<usize>)> {
+        let mut best_cost = self.objective.worst();
+        let mut first = 1;
         
-        while current_mask != (1 << self.n) - 1 {
-            if let Some(&next_city) = self.parent.get(&(current_mask, current_city)) {
-                path.push(next_city);
-                current_mask |= 1 << next_city;
-                current_city = next_city;
-            } else {
+        for next in (1..self.n).filter(|&c| self.bits[c] != 0 && self.required[c] == 0) {
+            let cost = self.objective.combine(self.distance_matrix[0][next],
+                                              self.cost[self.index(self.bits[next], next)]);
+            if self.objective.is_better(cost, best_cost) {
+                best_cost = cost;
+                first = next;
+            }
+        }
+        
+        if !best_cost.is_finite() {
+            return Err(TSPError::SolverError(
+                "No visiting order satisfies the precedence constraints".to_string()
+            ).into());
+        }
+        
+        // Among equally good successors, follow the tie-break policy instead of loop order
+        if self.tie_break != TieBreak::LoopOrder {
+            let mut path = vec![0];
+            let (mut mask, mut value) = (0, best_cost);
+            while let Some(&next) = self.tight_moves(mask, path[path.len() - 1], value).first() {
+                mask |= self.bits[next];
+                value = self.cost[self.index(mask, next)];
+                path.push(next);
+            }
+            return Ok((best_cost, path));
+        }
+        
+        let mut path = vec![0, first];
+        let mut current_mask = self.bits[first];
+        let mut current_city = first;
+        
+        loop {
+            let next_city = self.parent[self.index(current_mask, current_city)];
+            if next_city == NO_PARENT {
                 break;
             }
+            let next_city = next_city as usize;
+            path.push(next_city);
+            current_mask |= self.bits[next_city];
+            current_city = next_city;
         }
         
-        Ok(path)
+        Ok((best_cost, path))
     }
     
-    /// Count number of set bits
-    fn count_bits(&self, mut mask: usize) -> usize {
-        let mut count = 0;
-        while mask > 0 {
-            count += mask & 1;
-            mask >>= 1;
+    /// Number of (mask, city) states of the DP: each visitable city is in half the masks
+    fn total_states(&self) -> u64 {
+        let cities = (1..self.n).filter(|&c| self.bits[c] != 0).count() as u64;
+        cities << (self.mask_width - 1)
+    }
+    
+    /// Send a progress report, if anyone listens
+    fn report(&self, states: u64, layer: usize, started: Instant) {
+        if let Some(observer) = &self.progress {
+            observer.update(&Progress {
+                states,
+                total_states: self.total_states(),
+                layer,
+                layers: self.mask_width,
+                best_cost: self.greedy,
+                elapsed: started.elapsed(),
+            });
+        }
+    }
+    
+    /// States computed by the last finished `solve`, `solve_parallel` or co-optimal run
+    pub fn states(&self) -> u64 {
+        self.states
+    }
+    
+    /// Final report once the DP is done and the optimum known
+    fn finish_progress(&mut self, started: Instant, cost: f64, verbose: bool) {
+        let total = self.total_states();
+        self.states = total;
+        let progress = Progress {
+            states: total,
+            total_states: total,
+            layer: 1,
+            layers: self.mask_width,
+            best_cost: Some(cost),
+            elapsed: started.elapsed(),
+        };
+        if let Some(observer) = &self.progress {
+            observer.finish(&progress);
+        }
+        if verbose {
+            println!("    • {} states in {:.2?}", total, progress.elapsed);
+        }
+    }
+}
+
+/// Shortest closed route from `start` through every subset of `cities` (Held-Karp over all
+/// subsets, not just the full one); bit i of a subset mask stands for `cities[i]`
+pub struct SubsetTours {
+    cities: Vec<usize>,
+    start: usize,
+    /// Closed-route length per subset mask; the empty mask is the start alone
+    pub cost: Vec<f64>,
+    last: Vec<u8>,
+    parent: Vec<u8>,
+}
+
+impl SubsetTours {
+    pub fn new(distance_matrix: &[Vec<f64>], start: usize, cities: Vec<usize>) -> Self {
+        let d = distance_matrix;
+        let m = cities.len();
+        let full = (1usize << m) - 1;
+        
+        // path[mask * m + last] = shortest start -> mask path ending at cities[last]
+        let mut path = vec![f64::INFINITY; (full + 1) * m];
+        let mut parent = vec![NO_PARENT; (full + 1) * m];
+        for (i, &c) in cities.iter().enumerate() {
+            path[(1 << i) * m + i] = d[start][c];
+        }
+        
+        for mask in 1..=full {
+            for last in 0..m {
+                let here = path[mask * m + last];
+                if mask & (1 << last) == 0 || !here.is_finite() {
+                    continue;
+                }
+                for next in (0..m).filter(|&c| mask & (1 << c) == 0) {
+                    let index = (mask | 1 << next) * m + next;
+                    let cost = here + d[cities[last]][cities[next]];
+                    if cost < path[index] {
+                        path[index] = cost;
+                        parent[index] = last as u8;
+                    }
+                }
+            }
+        }
+        
+        let mut cost = vec![f64::INFINITY; full + 1];
+        let mut last_city = vec![NO_PARENT; full + 1];
+        cost[0] = 0.0;
+        for mask in 1..=full {
+            for last in (0..m).filter(|&c| mask & (1 << c) != 0) {
+                let closed = path[mask * m + last] + d[cities[last]][start];
+                if closed < cost[mask] {
+                    cost[mask] = closed;
+                    last_city[mask] = last as u8;
+                }
+            }
+        }
+        
+        Self {
+            cities,
+            start,
+            cost,
+            last: last_city,
+            parent,
+        }
+    }
+    
+    /// Optimal route through `mask`, starting with the start city
+    pub fn tour(&self, mask: usize) -> Vec<usize> {
+        let m = self.cities.len();
+        let mut route = Vec::with_capacity(mask.count_ones() as usize + 1);
+        let mut remaining = mask;
+        let mut city = self.last[mask];
+        while remaining != 0 {
+            route.push(self.cities[city as usize]);
+            let previous = self.parent[remaining * m + city as usize];
+            remaining &= !(1 << city);
+            city = previous;
+        }
+        route.push(self.start);
+        route.reverse();
+        route
+    }
+}
+
+/// Error of a DP stopped by its cancellation token
+fn cancelled() -> anyhow::Error {
+    TSPError::Cancelled("Held-Karp DP stopped before it finished".to_string()).into()
+}
+
+/// True when `b` is `a` travelled backwards (as a tour: from the same start city)
+fn is_reverse(a: &[usize], b: &[usize], route: Route) -> bool {
+    if a.len() != b.len() || a.is_empty() {
+        return false;
+    }
+    match route {
+        Route::Tour => a[0] == b[0] && a[1..].iter().eq(b[1..].iter().rev()),
+        _ => a.iter().eq(b.iter().rev()),
+    }
+}
+
+/// Label of `city` after city 0 and `start` swap places (its own inverse)
+fn swap_start(city: usize, start: usize) -> usize {
+    if city == start {
+        0
+    } else if city == 0 {
+        start
+    } else {
+        city
+    }
+}
+
+/// Total length of a closed tour
+pub fn tour_cost(matrix: &[Vec<f64>], path: &[usize]) -> f64 {
+    let mut cost = 0.0;
+    for i in 0..path.len() {
+        cost += matrix[path[i]][path[(i + 1) % path.len()]];
+    }
+    cost
+}
+
+/// Total length of an open path (no return leg)
+pub fn path_cost(matrix: &[Vec<f64>], path: &[usize]) -> f64 {
+    path.windows(2).map(|w| matrix[w[0]][w[1]]).sum()
+}
+
+/// Longest edge of `path` as a closed tour or an open path, as (from, to)
+pub fn bottleneck_edge(matrix: &[Vec<f64>], path: &[usize], route: Route) -> Option<(usize, usize)> {
+    let closing = path.last().zip(path.first()).filter(|_| route.is_closed());
+    path.windows(2)
+        .map(|w| (w[0], w[1]))
+        .chain(closing.map(|(&a, &b)| (a, b)))
+        .max_by(|&(a, b), &(c, d)| matrix[a][b].total_cmp(&matrix[c][d]))
+}
+
+/// Length of `path` as a closed tour or an open path
+pub fn route_cost(matrix: &[Vec<f64>], path: &[usize], route: Route) -> f64 {
+    if route.is_closed() {
+        tour_cost(matrix, path)
+    } else {
+        path_cost(matrix, path)
+    }
+}
+
+/// Closed tours rotated to begin at `start`; paths already run between their endpoints
+/// and keep their order
+pub fn from_start(mut path: Vec<usize>, start: usize, route: Route) -> Vec<usize> {
+    if route.is_closed() {
+        if let Some(offset) = path.iter().position(|&c| c == start) {
+            path.rotate_left(offset);
+        }
+    }
+    path
+}
+
+/// One sample of a metaheuristic run, for convergence charts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConvergencePoint {
+    pub iteration: usize,
+    /// Cost of the current solution (population mean for population-based solvers)
+    pub current: f64,
+    pub best: f64,
+}
+
+/// True when d[i][j] == d[j][i] for every pair
+pub fn is_symmetric(matrix: &[Vec<f64>]) -> bool {
+    (0..matrix.len()).all(|i| (0..i).all(|j| matrix[i][j] == matrix[j][i]))
+}
+
+/// Iterates every mask of `width` bits with exactly `ones` bits set, in increasing order
+struct LayerMasks {
+    next: usize,
+    limit: usize,
+}
+
+impl LayerMasks {
+    fn new(width: usize, ones: usize) -> Self {
+        Self {
+            next: (1 << ones) - 1,
+            limit: 1 << width,
         }
-        count
+    }
+}
+
+impl Iterator for LayerMasks {
+    type Item = usize;
+    
+    fn next(&mut self) -> Option<usize> {
+        let mask = self.next;
+        if mask >= self.limit || mask == 0 {
+            return None;
+        }
+        
+        // Gosper's hack: next larger number with the same popcount
+        let lowest = mask & mask.wrapping_neg();
+        let ripple = mask + lowest;
+        self.next = (((ripple ^ mask) >> 2) / lowest) | ripple;
+        
+        Some(mask)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{pseudo_random_matrix, ProgressRecorder};
     
     #[test]
     fn test_small_tsp() {
@@ -126,7 +1306,7 @@ mod tests {
         
         let mut solver = TSPSolver::new(matrix);
         let (cost, path) = solver.solve(false).unwrap();
-    
+        
         assert_eq!(cost, 45.0);
         assert_eq!(path, vec![0, 1, 2]);
     }
@@ -140,4 +1320,335 @@ mod tests {
         assert_eq!(cost, 0.0);
         assert_eq!(path, vec![0]);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_asymmetric_tsp() {
+        // Classic 4-city instance, optimum 0 -> 1 -> 3 -> 2 -> 0
+        let matrix = vec![
+            vec![0.0, 10.0, 15.0, 20.0],
+            vec![5.0, 0.0, 9.0, 10.0],
+            vec![6.0, 13.0, 0.0, 12.0],
+            vec![8.0, 8.0, 9.0, 0.0],
+        ];
+        
+        let mut solver = TSPSolver::new(matrix);
+        let (cost, path) = solver.solve(false).unwrap();
+        
+        assert_eq!(cost, 35.0);
+        assert_eq!(path, vec![0, 1, 3, 2]);
+    }
+    
+    #[test]
+    fn test_open_paths() {
+        let matrix = vec![
+            vec![0.0, 10.0, 15.0, 20.0],
+            vec![10.0, 0.0, 35.0, 25.0],
+            vec![15.0, 35.0, 0.0, 30.0],
+            vec![20.0, 25.0, 30.0, 0.0],
+        ];
+        
+        let solve = |route| TSPSolver::new(matrix.clone()).with_route(route).solve_parallel(false, 2).unwrap();
+        
+        assert_eq!(solve(Route::Path { end: None }), (65.0, vec![0, 1, 3, 2]));
+        assert_eq!(solve(Route::Path { end: Some(1) }), (70.0, vec![0, 2, 3, 1]));
+        
+        // Best free path does not start at city 0
+        let (cost, path) = solve(Route::FreePath);
+        assert_eq!(cost, 50.0);
+        assert!(path == vec![2, 0, 1, 3] || path == vec![3, 1, 0, 2]);
+        
+        // Reporting from the start city must not turn the path into a different one
+        let reported = from_start(path, 0, Route::FreePath);
+        assert_eq!(route_cost(&matrix, &reported, Route::FreePath), cost);
+        assert_eq!(from_start(vec![2, 0, 1, 3], 0, Route::Tour), vec![0, 1, 3, 2]);
+    }
+    
+    #[test]
+    fn test_start_city() {
+        let matrix = vec![
+            vec![0.0, 10.0, 15.0, 20.0],
+            vec![10.0, 0.0, 35.0, 25.0],
+            vec![15.0, 35.0, 0.0, 30.0],
+            vec![20.0, 25.0, 30.0, 0.0],
+        ];
+        
+        let solve = |route| TSPSolver::new(matrix.clone()).with_start(2).with_route(route).solve(false).unwrap();
+        
+        // Same tour as from city 0 (0 -> 1 -> 3 -> 2), reported from city 2
+        let (cost, path) = solve(Route::Tour);
+        assert_eq!(cost, 80.0);
+        assert!(path == vec![2, 0, 1, 3] || path == vec![2, 3, 1, 0]);
+        
+        // Open paths from city 2 differ from those from city 0
+        assert_eq!(solve(Route::Path { end: None }), (50.0, vec![2, 0, 1, 3]));
+        assert_eq!(solve(Route::Path { end: Some(1) }), (60.0, vec![2, 0, 3, 1]));
+    }
+    
+    #[test]
+    fn test_precedence() {
+        let matrix = vec![
+            vec![0.0, 10.0, 15.0, 20.0],
+            vec![10.0, 0.0, 35.0, 25.0],
+            vec![15.0, 35.0, 0.0, 30.0],
+            vec![20.0, 25.0, 30.0, 0.0],
+        ];
+        let solve = |precedence: Vec<(usize, usize)>, start| {
+            TSPSolver::new(matrix.clone()).with_start(start).with_precedence(precedence).solve_parallel(false, 2)
+        };
+        
+        // Neither direction of the optimum (0 -> 1 -> 3 -> 2, 80) keeps 1 before 2 before 3
+        assert_eq!(solve(vec![(1, 2), (2, 3)], 0).unwrap(), (95.0, vec![0, 1, 2, 3]));
+        assert_eq!(solve(vec![(2, 1), (3, 2)], 0).unwrap(), (95.0, vec![0, 3, 2, 1]));
+        assert_eq!(solve(vec![(1, 0)], 2).unwrap(), (80.0, vec![2, 3, 1, 0]));
+        
+        // Cycle, and a city required before the start
+        assert!(solve(vec![(1, 2), (2, 3), (3, 1)], 0).is_err());
+        assert!(solve(vec![(1, 0)], 0).is_err());
+    }
+    
+    #[test]
+    fn test_clusters() {
+        let n = 9;
+        let matrix = pseudo_random_matrix(n, false);
+        let clusters = vec![0, 1, 1, 2, 2, 2, 3, 4, 4];
+        
+        // Brute force: one city from each cluster, any member of the start's cluster
+        let mut expected = f64::INFINITY;
+        for (a, b, c) in (3..6).flat_map(|c| [(1, 7, c), (1, 8, c), (2, 7, c), (2, 8, c)]) {
+            let cities = [c, 0, a, 6, b];
+            let sub: Vec<Vec<f64>> = cities.iter()
+                .map(|&a| cities.iter().map(|&b| matrix[a][b]).collect())
+                .collect();
+            expected = expected.min(TSPSolver::new(sub).solve(false).unwrap().0);
+        }
+        
+        for threads in [1, 3] {
+            let (cost, path) = TSPSolver::new(matrix.clone())
+                .with_start(4)
+                .with_clusters(clusters.clone())
+                .solve_parallel(false, threads)
+                .unwrap();
+            
+            let mut visited: Vec<usize> = path.iter().map(|&c| clusters[c]).collect();
+            visited.sort();
+            assert_eq!(visited, vec![0, 1, 2, 3, 4]);
+            assert_eq!(clusters[path[0]], 2);
+            assert!((cost - expected).abs() < 1e-9);
+            assert!((tour_cost(&matrix, &path) - cost).abs() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_clusters_replace_the_start() {
+        // City 0 is far from everything; city 1 shares its cluster and is close by
+        let matrix = vec![
+            vec![0.0, 50.0, 50.0, 50.0],
+            vec![50.0, 0.0, 1.0, 2.0],
+            vec![50.0, 1.0, 0.0, 1.0],
+            vec![50.0, 2.0, 1.0, 0.0],
+        ];
+        let solver = || TSPSolver::new(matrix.clone()).with_clusters(vec![0, 0, 1, 2]);
+        
+        let (cost, path) = solver().solve_parallel(false, 2).unwrap();
+        assert_eq!((cost, path[0]), (4.0, 1));
+        assert_eq!(solver().solve_k_best(2, false).unwrap()[0].0, 4.0);
+        let optimal = solver().solve_co_optimal(5, false).unwrap();
+        assert_eq!((optimal.cost, optimal.count), (4.0, 1));
+        
+        // Paths still begin at the start city
+        let (cost, path) = solver().with_route(Route::Path { end: None }).solve(false).unwrap();
+        assert_eq!((cost, path[0]), (51.0, 0));
+    }
+    
+    #[test]
+    fn test_objectives() {
+        let n = 7;
+        let matrix = pseudo_random_matrix(n, false);
+        
+        // Brute force over all orders of cities 1..n
+        let mut orders = vec![vec![0]];
+        for _ in 1..n {
+            orders = orders.iter()
+                .flat_map(|o| (1..n).filter(|c| !o.contains(c)).map(|c| [o.clone(), vec![c]].concat()))
+                .collect();
+        }
+        let longest_edge = |path: &Vec<usize>, route| {
+            let (a, b) = bottleneck_edge(&matrix, path, route).unwrap();
+            matrix[a][b]
+        };
+        
+        for (objective, route) in [
+            (TourObjective::MinBottleneck, Route::Tour),
+            (TourObjective::MinBottleneck, Route::Path { end: None }),
+            (TourObjective::MaxTotal, Route::Tour),
+        ] {
+            let (cost, path) = TSPSolver::new(matrix.clone())
+                .with_objective(objective)
+                .with_route(route)
+                .solve_parallel(false, 2)
+                .unwrap();
+            
+            let (achieved, expected) = match objective {
+                TourObjective::MaxTotal => (
+                    route_cost(&matrix, &path, route),
+                    orders.iter().map(|o| route_cost(&matrix, o, route)).fold(0.0, f64::max),
+                ),
+                _ => (
+                    longest_edge(&path, route),
+                    orders.iter().map(|o| longest_edge(o, route)).fold(f64::INFINITY, f64::min),
+                ),
+            };
+            assert_eq!(cost, expected, "{:?} {:?}", objective, route);
+            assert_eq!(achieved, expected, "{:?} {:?}", objective, route);
+        }
+    }
+    
+    #[test]
+    fn test_k_best() {
+        let n = 7;
+        for symmetric in [false, true] {
+            let matrix = pseudo_random_matrix(n, symmetric);
+            
+            // Brute force: every order of cities 1..n, one direction only when symmetric
+            let mut orders = vec![vec![0]];
+            for _ in 1..n {
+                orders = orders.iter()
+                    .flat_map(|o| (1..n).filter(|c| !o.contains(c)).map(|c| [o.clone(), vec![c]].concat()))
+                    .collect();
+            }
+            let mut expected: Vec<f64> = orders.iter()
+                .filter(|o| !symmetric || o[1] < o[n - 1])
+                .map(|o| tour_cost(&matrix, o))
+                .collect();
+            expected.sort_by(f64::total_cmp);
+            
+            let tours = TSPSolver::new(matrix.clone()).solve_k_best(6, false).unwrap();
+            let costs: Vec<f64> = tours.iter().map(|(c, _)| *c).collect();
+            assert_eq!(costs, expected[..6].to_vec(), "symmetric: {}", symmetric);
+            for (cost, path) in &tours {
+                assert_eq!(tour_cost(&matrix, path), *cost);
+            }
+            for (i, (_, a)) in tours.iter().enumerate() {
+                assert!(tours[..i].iter().all(|(_, b)| b != a && !is_reverse(a, b, Route::Tour)));
+            }
+        }
+        
+        // Only reversible routes need twice as many ranks as tours asked for
+        let tours = TSPSolver::new(pseudo_random_matrix(n, false)).solve_k_best(200, false).unwrap();
+        assert_eq!(tours.len(), 200);
+        let err = TSPSolver::new(pseudo_random_matrix(n, true)).solve_k_best(200, false).unwrap_err();
+        assert!(err.to_string().contains("between 1 and 127"), "{}", err);
+    }
+    
+    #[test]
+    fn test_co_optimal() {
+        // Every tour costs 4.5 when it uses edge 0-4 and one of 0-1 and 0-2
+        let mut matrix = vec![vec![1.0; 5]; 5];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[i] = 0.0;
+        }
+        matrix[0][4] = 0.5;
+        matrix[4][0] = 0.5;
+        matrix[0][3] = 1.5;
+        matrix[3][0] = 1.5;
+        
+        let optimal = TSPSolver::new(matrix.clone())
+            .with_tie_break(TieBreak::Lexicographic((0..5).collect()))
+            .solve_co_optimal(10, false)
+            .unwrap();
+        assert_eq!(optimal.cost, 4.5);
+        assert_eq!(optimal.count, 4);
+        assert_eq!(optimal.routes, vec![vec![0, 1, 2, 3, 4], vec![0, 1, 3, 2, 4],
+                                        vec![0, 2, 1, 3, 4], vec![0, 2, 3, 1, 4]]);
+        
+        let shortest_leg = TieBreak::ShortestFirstLeg((0..5).collect());
+        let mut solver = TSPSolver::new(matrix.clone()).with_tie_break(shortest_leg);
+        assert_eq!(solver.solve(false).unwrap(), (4.5, vec![0, 4, 1, 3, 2]));
+        
+        // Free paths: all 12 undirected paths through 4 equidistant cities tie
+        let equal = vec![vec![0.0, 1.0, 1.0, 1.0], vec![1.0, 0.0, 1.0, 1.0],
+                         vec![1.0, 1.0, 0.0, 1.0], vec![1.0, 1.0, 1.0, 0.0]];
+        let mut solver = TSPSolver::new(equal)
+            .with_route(Route::FreePath)
+            .with_tie_break(TieBreak::Lexicographic(vec![3, 2, 1, 0]));
+        let optimal = solver.solve_co_optimal(5, false).unwrap();
+        assert_eq!(optimal.count, 12);
+        assert_eq!(optimal.routes.len(), 5);
+        assert_eq!(optimal.routes[0], vec![3, 2, 1, 0]);
+        assert_eq!(solver.solve(false).unwrap().1, vec![3, 2, 1, 0]);
+    }
+    
+    #[test]
+    fn test_cancel() {
+        let matrix = pseudo_random_matrix(14, false);
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        
+        for threads in [1, 2] {
+            let error = TSPSolver::new(matrix.clone())
+                .with_route(Route::FreePath)
+                .with_cancel(cancel.clone())
+                .solve_parallel(false, threads)
+                .unwrap_err();
+            assert!(matches!(error.downcast_ref::<TSPError>(), Some(TSPError::Cancelled(_))));
+        }
+        assert!(TSPSolver::new(matrix).with_cancel(CancelToken::new()).solve(false).is_ok());
+    }
+    
+    #[test]
+    fn test_progress_reports() {
+        let matrix = pseudo_random_matrix(13, false);
+        for threads in [1, 2] {
+            let recorder = Arc::new(ProgressRecorder::default());
+            let (cost, _) = TSPSolver::new(matrix.clone())
+                .with_progress(recorder.clone())
+                .solve_parallel(false, threads)
+                .unwrap();
+            
+            let reports = recorder.0.lock().unwrap();
+            assert!(reports.len() > 2);
+            // A greedy tour is known from the first report on
+            assert!(reports.iter().all(|p| p.best_cost.is_some_and(|best| best >= cost)));
+            for pair in reports.windows(2) {
+                assert!(pair[0].states <= pair[1].states && pair[0].layer >= pair[1].layer);
+            }
+            let last = reports.last().unwrap();
+            assert_eq!(last.best_cost, Some(cost));
+            assert_eq!((last.states, last.total_states), (12 << 11, 12 << 11));
+        }
+    }
+    
+    #[test]
+    fn test_states_of_reduced_solvers() {
+        // Start swaps and free paths solve a reduced copy; its state count is reported
+        let matrix = pseudo_random_matrix(6, false);
+        for threads in [1, 2] {
+            let mut started = TSPSolver::new(matrix.clone()).with_start(3);
+            started.solve_parallel(false, threads).unwrap();
+            assert_eq!(started.states(), 5 << 4);
+            
+            let mut free = TSPSolver::new(matrix.clone()).with_route(Route::FreePath);
+            free.solve_parallel(false, threads).unwrap();
+            assert_eq!(free.states(), 6 << 5);
+        }
+    }
+    
+    #[test]
+    fn test_parallel_matches_serial() {
+        // Deterministic pseudo-random asymmetric 11-city matrix
+        let n = 11;
+        let matrix: Vec<Vec<f64>> = pseudo_random_matrix(n, false).into_iter()
+            .map(|row| row.into_iter().map(|d| d * 0.7).collect())
+            .collect();
+        
+        let (serial_cost, serial_path) = TSPSolver::new(matrix.clone()).solve(false).unwrap();
+        for threads in [2, 3, 8] {
+            let (cost, path) = TSPSolver::new(matrix.clone())
+                .solve_parallel(false, threads)
+                .unwrap();
+            
+            assert_eq!(cost.to_bits(), serial_cost.to_bits());
+            assert_eq!(path, serial_path);
+        }
+    }
+}
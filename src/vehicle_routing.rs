@@ -0,0 +1,367 @@
+use anyhow::Result;
+
+use crate::local_search::LocalSearch;
+use crate::tsp_solver::{tour_cost, TSPSolver};
+use crate::TSPError;
+
+/// Routes up to this many cities (depot included) are re-solved exactly with Held-Karp
+const EXACT_ROUTE_SIZE: usize = 12;
+
+/// Power-iteration steps per coordinate when embedding the matrix in the plane
+const EMBEDDING_ITERATIONS: usize = 200;
+
+/// Customer demands and vehicle capacity from the input
+#[derive(Clone, Debug, PartialEq)]
+pub struct Demands {
+    pub demand: Vec<f64>,
+    pub capacity: f64,
+}
+
+/// How customers are split into vehicle routes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Split {
+    /// Clarke-Wright savings: merge routes whose joint saves the most distance
+    Savings,
+    /// Sweep: fill vehicles in order of angle around the depot
+    Sweep,
+}
+
+/// Capacitated vehicle routing: split customers into routes from the depot that each
+/// carry at most the capacity, then optimise every route as a TSP
+pub struct VehicleRouting {
+    distance_matrix: Vec<Vec<f64>>,
+    demand: Vec<f64>,
+    capacity: f64,
+    n: usize,
+    depot: usize,
+    split: Split,
+}
+
+impl VehicleRouting {
+    pub fn new(distance_matrix: Vec<Vec<f64>>, demands: Demands) -> Self {
+        let n = distance_matrix.len();
+        Self {
+            distance_matrix,
+            demand: demands.demand,
+            capacity: demands.capacity,
+            n,
+            depot: 0,
+            split: Split::Savings,
+        }
+    }
+    
+    pub fn with_depot(mut self, depot: usize) -> Self {
+        self.depot = depot;
+        self
+    }
+    
+    pub fn with_split(mut self, split: Split) -> Self {
+        self.split = split;
+        self
+    }
+    
+    /// Returns the total distance and the routes, each a closed tour starting at the depot
+    pub fn solve(&self, verbose: bool) -> Result<(f64, Vec<Vec<usize>>)> {
+        self.solve_with(verbose, VehicleRouting::route_tour)
+    }
+    
+    /// Like `solve`, with `solve_route` finding the tour through each route's matrix
+    /// (depot first); routes of up to three cities are always solved exactly
+    pub fn solve_with(&self, verbose: bool,
+                      mut solve_route: impl FnMut(Vec<Vec<f64>>) -> Result<Vec<usize>>) -> Result<(f64, Vec<Vec<usize>>)> {
+        self.validate()?;
+        
+        let groups = match self.split {
+            Split::Savings => self.savings(),
+            Split::Sweep => self.sweep(),
+        };
+        let before: f64 = groups.iter().map(|g| self.route_cost(g)).sum();
+        println!("  • {} routes, {:.1} before per-route optimisation", groups.len(), before);
+        
+        let mut routes = Vec::with_capacity(groups.len());
+        for (i, group) in groups.iter().enumerate() {
+            let route = self.optimise_route(group, &mut solve_route)?;
+            if verbose {
+                println!("    • Route {}: {} customers, load {:.1}, {:.1} -> {:.1}",
+                        i + 1, group.len(), self.load(group), self.route_cost(group), tour_cost(&self.distance_matrix, &route));
+            }
+            routes.push(route);
+        }
+        
+        let total = routes.iter().map(|r| tour_cost(&self.distance_matrix, r)).sum();
+        Ok((total, routes))
+    }
+    
+    /// Total demand of a group of customers
+    pub fn load(&self, customers: &[usize]) -> f64 {
+        customers.iter().map(|&c| self.demand[c]).sum()
+    }
+    
+    fn validate(&self) -> Result<()> {
+        if self.demand[self.depot] != 0.0 {
+            return Err(TSPError::GraphValidation(
+                format!("Depot (city {}) cannot have a demand", self.depot)
+            ).into());
+        }
+        
+        if let Some(c) = (0..self.n).find(|&c| self.demand[c] > self.capacity) {
+            return Err(TSPError::GraphValidation(
+                format!("Demand of city {} ({}) exceeds the vehicle capacity ({})", c, self.demand[c], self.capacity)
+            ).into());
+        }
+        
+        Ok(())
+    }
+    
+    fn customers(&self) -> Vec<usize> {
+        (0..self.n).filter(|&c| c != self.depot).collect()
+    }
+    
+    /// Length of depot -> customers in order -> depot
+    fn route_cost(&self, customers: &[usize]) -> f64 {
+        let d = &self.distance_matrix;
+        let mut cost = 0.0;
+        let mut previous = self.depot;
+        for &c in customers {
+            cost += d[previous][c];
+            previous = c;
+        }
+        cost + d[previous][self.depot]
+    }
+    
+    /// Clarke-Wright parallel savings on the symmetrised matrix; orientation is
+    /// settled by the per-route optimisation afterwards
+    fn savings(&self) -> Vec<Vec<usize>> {
+        let d = &self.distance_matrix;
+        let depot = self.depot;
+        let symmetric = |i: usize, j: usize| (d[i][j] + d[j][i]) / 2.0;
+        
+        let customers = self.customers();
+        let mut routes: Vec<Option<Vec<usize>>> = customers.iter().map(|&c| Some(vec![c])).collect();
+        let mut route_of = vec![usize::MAX; self.n];
+        let mut loads: Vec<f64> = customers.iter().map(|&c| self.demand[c]).collect();
+        for (r, &c) in customers.iter().enumerate() {
+            route_of[c] = r;
+        }
+        
+        let mut savings: Vec<(f64, usize, usize)> = Vec::new();
+        for (a, &i) in customers.iter().enumerate() {
+            for &j in &customers[a + 1..] {
+                let saving = symmetric(i, depot) + symmetric(depot, j) - symmetric(i, j);
+                if saving > 0.0 {
+                    savings.push((saving, i, j));
+                }
+            }
+        }
+        savings.sort_by(|a, b| b.0.total_cmp(&a.0));
+        
+        for (_, i, j) in savings {
+            let (ri, rj) = (route_of[i], route_of[j]);
+            if ri == rj || loads[ri] + loads[rj] > self.capacity {
+                continue;
+            }
+            
+            let mut first = routes[ri].take().unwrap();
+            let mut second = routes[rj].take().unwrap();
+            
+            // Join only at route ends: `i` must end the first route and `j` start the second
+            if first.last() != Some(&i) {
+                first.reverse();
+            }
+            if second.first() != Some(&j) {
+                second.reverse();
+            }
+            if first.last() != Some(&i) || second.first() != Some(&j) {
+                routes[ri] = Some(first);
+                routes[rj] = Some(second);
+                continue;
+            }
+            
+            for &c in &second {
+                route_of[c] = ri;
+            }
+            first.extend(second);
+            routes[ri] = Some(first);
+            loads[ri] += loads[rj];
+        }
+        
+        routes.into_iter().flatten().collect()
+    }
+    
+    /// Sweep by angle around the depot in a planar embedding of the matrix, starting
+    /// from whichever customer gives the shortest unoptimised routes
+    fn sweep(&self) -> Vec<Vec<usize>> {
+        let points = embed_in_plane(&self.distance_matrix);
+        let (dx, dy) = points[self.depot];
+        
+        let mut order = self.customers();
+        order.sort_by(|&a, &b| {
+            let angle = |c: usize| (points[c].1 - dy).atan2(points[c].0 - dx);
+            angle(a).total_cmp(&angle(b))
+        });
+        
+        (0..order.len())
+            .map(|offset| {
+                let mut rotated = order.clone();
+                rotated.rotate_left(offset);
+                self.fill_vehicles(&rotated)
+            })
+            .min_by(|a, b| {
+                let cost = |groups: &Vec<Vec<usize>>| groups.iter().map(|g| self.route_cost(g)).sum::<f64>();
+                cost(a).total_cmp(&cost(b))
+            })
+            .unwrap_or_default()
+    }
+    
+    /// Cut `order` into consecutive groups that fit the capacity
+    fn fill_vehicles(&self, order: &[usize]) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut load = f64::INFINITY;
+        
+        for &c in order {
+            if load + self.demand[c] > self.capacity {
+                groups.push(Vec::new());
+                load = 0.0;
+            }
+            groups.last_mut().unwrap().push(c);
+            load += self.demand[c];
+        }
+        
+        groups
+    }
+    
+    /// Default route solver: Held-Karp for small routes, local search from the given
+    /// order otherwise
+    pub fn route_tour(matrix: Vec<Vec<f64>>) -> Result<Vec<usize>> {
+        if matrix.len() <= EXACT_ROUTE_SIZE {
+            return Ok(TSPSolver::new(matrix).solve(false)?.1);
+        }
+        let mut order: Vec<usize> = (0..matrix.len()).collect();
+        LocalSearch::new(matrix).improve_quietly(&mut order);
+        Ok(order)
+    }
+    
+    /// Tour through the depot and `customers`, rotated to start at the depot
+    fn optimise_route(&self, customers: &[usize],
+                      solve_route: &mut impl FnMut(Vec<Vec<f64>>) -> Result<Vec<usize>>) -> Result<Vec<usize>> {
+        let cities: Vec<usize> = std::iter::once(self.depot).chain(customers.iter().copied()).collect();
+        let matrix: Vec<Vec<f64>> = cities.iter()
+            .map(|&a| cities.iter().map(|&b| self.distance_matrix[a][b]).collect())
+            .collect();
+        
+        let mut local = if cities.len() <= 3 {
+            VehicleRouting::route_tour(matrix)?
+        } else {
+            solve_route(matrix)?
+        };
+        
+        if let Some(depot) = local.iter().position(|&c| c == 0) {
+            local.rotate_left(depot);
+        }
+        Ok(local.into_iter().map(|c| cities[c]).collect())
+    }
+}
+
+/// Classical multidimensional scaling: points whose distances approximate the
+/// (symmetrised) matrix, from the top two eigenvectors of the double-centred squares
+fn embed_in_plane(matrix: &[Vec<f64>]) -> Vec<(f64, f64)> {
+    let n = matrix.len();
+    let squared: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| ((matrix[i][j] + matrix[j][i]) / 2.0).powi(2)).collect())
+        .collect();
+    let row_mean: Vec<f64> = squared.iter().map(|r| r.iter().sum::<f64>() / n as f64).collect();
+    let mean = row_mean.iter().sum::<f64>() / n as f64;
+    let mut centred: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| -0.5 * (squared[i][j] - row_mean[i] - row_mean[j] + mean)).collect())
+        .collect();
+    
+    let mut axes = Vec::with_capacity(2);
+    for axis in 0..2 {
+        // Power iteration from a fixed, non-symmetric start vector
+        let mut v: Vec<f64> = (0..n).map(|i| 1.0 + ((i * 7 + axis * 3) % 11) as f64).collect();
+        let mut eigenvalue = 0.0;
+        for _ in 0..EMBEDDING_ITERATIONS {
+            let w: Vec<f64> = centred.iter().map(|row| row.iter().zip(&v).map(|(a, b)| a * b).sum()).collect();
+            let norm = w.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm < 1e-12 {
+                break;
+            }
+            eigenvalue = v.iter().zip(&w).map(|(a, b)| a * b).sum::<f64>()
+                / v.iter().map(|x| x * x).sum::<f64>();
+            v = w.into_iter().map(|x| x / norm).collect();
+        }
+        
+        // Deflate so the next axis finds the second eigenvector
+        for i in 0..n {
+            for j in 0..n {
+                centred[i][j] -= eigenvalue * v[i] * v[j];
+            }
+        }
+        let scale = eigenvalue.max(0.0).sqrt();
+        axes.push(v.into_iter().map(|x| x * scale).collect::<Vec<f64>>());
+    }
+    
+    (0..n).map(|i| (axes[0][i], axes[1][i])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::points_matrix;
+    
+    #[test]
+    fn test_routes_respect_capacity() {
+        let n = 25;
+        let demand: Vec<f64> = (0..n).map(|i| if i == 3 { 0.0 } else { (1 + i % 4) as f64 }).collect();
+        
+        for split in [Split::Savings, Split::Sweep] {
+            let vrp = VehicleRouting::new(points_matrix(n), Demands { demand: demand.clone(), capacity: 10.0 })
+                .with_depot(3)
+                .with_split(split);
+            let (total, routes) = vrp.solve(false).unwrap();
+            
+            let mut visited: Vec<usize> = routes.iter().flat_map(|r| r[1..].iter().copied()).collect();
+            visited.sort();
+            assert_eq!(visited, (0..n).filter(|&c| c != 3).collect::<Vec<_>>(), "{:?}", split);
+            for route in &routes {
+                assert_eq!(route[0], 3);
+                assert!(vrp.load(&route[1..]) <= 10.0);
+            }
+            
+            let sum: f64 = routes.iter().map(|r| tour_cost(&points_matrix(n), r)).sum();
+            assert!((total - sum).abs() < 1e-9);
+        }
+    }
+    
+    #[test]
+    fn test_embedding_recovers_planar_distances() {
+        let matrix = points_matrix(10);
+        let points = embed_in_plane(&matrix);
+        for i in 0..10 {
+            for j in 0..10 {
+                let (a, b) = (points[i], points[j]);
+                let distance = ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+                assert!((distance - matrix[i][j]).abs() < 1e-6, "{} vs {}", distance, matrix[i][j]);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_savings_merges_neighbours() {
+        // Two pairs of customers far apart on opposite sides of the depot
+        let points = [(0.0, 0.0), (10.0, 0.0), (11.0, 0.0), (-10.0, 0.0), (-11.0, 0.0)];
+        let matrix: Vec<Vec<f64>> = points.iter()
+            .map(|a: &(f64, f64)| points.iter().map(|b| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()).collect())
+            .collect();
+        let vrp = VehicleRouting::new(matrix, Demands { demand: vec![0.0, 1.0, 1.0, 1.0, 1.0], capacity: 2.0 });
+        
+        let (total, mut routes) = vrp.solve(false).unwrap();
+        for route in &mut routes {
+            route[1..].sort();
+        }
+        routes.sort();
+        
+        assert_eq!(routes, vec![vec![0, 1, 2], vec![0, 3, 4]]);
+        assert_eq!(total, 44.0);
+    }
+}
@@ -1,7 +1,16 @@
 use anyhow::Result;
+use plotters::coord::types::RangedCoordf64;
 use plotters::prelude::*;
 use std::f64::consts::PI;
 
+use crate::tsp_solver::ConvergencePoint;
+
+/// Colour of cities a route does not visit
+const SKIPPED: RGBColor = RGBColor(180, 180, 180);
+
+/// Chart over the unit-circle city layout
+type Chart<'a> = ChartContext<'a, BitMapBackend<'a>, Cartesian2d<RangedCoordf64, RangedCoordf64>>;
+
 pub struct Visualizer;
 
 impl Visualizer {
@@ -13,6 +22,7 @@ impl Visualizer {
         &self,
         cities: &[String],
         path: &[usize],
+        closed: bool,
         min_cost: f64,
         output_file: &str,
     ) -> Result<()> {
@@ -21,7 +31,7 @@ impl Visualizer {
         
         let mut chart = ChartBuilder::on(&root)
             .caption(
-                &format!("TSP Solution - Total Distance: {:.1}", min_cost),
+                format!("TSP Solution - Total Distance: {:.1}", min_cost),
                 ("Arial", 30).into_font(),
             )
             .margin(40)
@@ -37,12 +47,165 @@ impl Visualizer {
         // Membuat posisi 
         let city_positions = self.generate_city_positions(cities.len());
         
-        // Gambar
-        for (i, (x, y)) in city_positions.iter().enumerate() {
+        // Cities the path leaves out (prize-collecting, cluster alternatives) are greyed out
+        let mut visited = vec![false; cities.len()];
+        for &city in path {
+            visited[city] = true;
+        }
+        self.draw_cities(&mut chart, cities, &city_positions, &visited)?;
+        if visited.contains(&false) {
+            chart.draw_series(std::iter::empty::<Circle<(f64, f64), i32>>())?
+                .label("Unvisited city")
+                .legend(|(x, y)| Circle::new((x + 5, y), 5, SKIPPED.filled()));
+        }
+        
+        // Gambar path
+        let mut path_points = Vec::new();
+        for &city_idx in path {
+            path_points.push(city_positions[city_idx]);
+        }
+        // Close loop (open paths stop at the last city)
+        if closed && !path.is_empty() {
+            path_points.push(city_positions[path[0]]);
+        }
+        
+        chart.draw_series(LineSeries::new(path_points.clone(), RED.stroke_width(3)))?
+            .label("Optimal Path")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], RED));
+        
+        self.draw_arrows(&mut chart, &path_points, RED.to_rgba())?;
+        
+        chart.configure_series_labels().draw()?;
+        
+        // Path information
+        let mut path_text = format!(
+            "Path: {}",
+            path.iter().map(|&i| cities[i].as_str()).collect::<Vec<_>>().join(" → ")
+        );
+        if closed {
+            path_text.push_str(&format!(" → {}", cities[path[0]]));
+        }
+        
+        chart.draw_series(std::iter::once(Text::new(
+            path_text,
+            (-1.1, -1.1),
+            ("Arial", 12).into_font().color(&BLACK),
+        )))?;
+        
+        root.present()?;
+        println!("  • Visualization created with {} cities", cities.len());
+        
+        Ok(())
+    }
+    
+    /// Routes from the depot, one colour per route; `title` is the full caption, since
+    /// vehicle routing and multiple salesmen report different objectives
+    pub fn create_routes_visualization(
+        &self,
+        cities: &[String],
+        routes: &[Vec<usize>],
+        title: &str,
+        output_file: &str,
+    ) -> Result<()> {
+        let root = BitMapBackend::new(output_file, (800, 600)).into_drawing_area();
+        root.fill(&WHITE)?;
+        
+        let mut chart = ChartBuilder::on(&root)
+            .caption(title, ("Arial", 30).into_font())
+            .margin(40)
+            .x_label_area_size(50)
+            .y_label_area_size(50)
+            .build_cartesian_2d(-1.2f64..1.2f64, -1.2f64..1.2f64)?;
+        
+        chart.configure_mesh()
+            .x_desc("X Coordinate")
+            .y_desc("Y Coordinate")
+            .draw()?;
+        
+        let city_positions = self.generate_city_positions(cities.len());
+        self.draw_cities(&mut chart, cities, &city_positions, &vec![true; cities.len()])?;
+        
+        for (i, route) in routes.iter().enumerate() {
+            let color = Palette99::pick(i).to_rgba();
+            let mut points: Vec<(f64, f64)> = route.iter().map(|&c| city_positions[c]).collect();
+            if let Some(&depot) = route.first() {
+                points.push(city_positions[depot]);
+            }
+            
+            chart.draw_series(LineSeries::new(points.clone(), color.stroke_width(3)))?
+                .label(format!("Route {}", i + 1))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], color));
+            self.draw_arrows(&mut chart, &points, color)?;
+        }
+        
+        chart.configure_series_labels().draw()?;
+        
+        root.present()?;
+        println!("  • Visualization created with {} routes", routes.len());
+        
+        Ok(())
+    }
+    
+    /// Line chart of current (or population mean) and best cost per iteration of a metaheuristic;
+    /// `series` labels the first line
+    pub fn create_convergence_chart(
+        &self,
+        trace: &[ConvergencePoint],
+        title: &str,
+        series: &str,
+        output_file: &str,
+    ) -> Result<()> {
+        let root = BitMapBackend::new(output_file, (800, 600)).into_drawing_area();
+        root.fill(&WHITE)?;
+        
+        let last_iteration = trace.last().map_or(1, |p| p.iteration.max(1));
+        let (low, high) = trace.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+            (lo.min(p.best).min(p.current), hi.max(p.best).max(p.current))
+        });
+        let margin = ((high - low) * 0.05).max(1e-6);
+        
+        let mut chart = ChartBuilder::on(&root)
+            .caption(format!("{} Convergence", title), ("Arial", 30).into_font())
+            .margin(40)
+            .x_label_area_size(50)
+            .y_label_area_size(70)
+            .build_cartesian_2d(0..last_iteration, (low - margin)..(high + margin))?;
+        
+        chart.configure_mesh()
+            .x_desc("Iteration")
+            .y_desc("Cost")
+            .draw()?;
+        
+        chart.draw_series(LineSeries::new(
+            trace.iter().map(|p| (p.iteration, p.current)),
+            BLUE.stroke_width(1),
+        ))?
+            .label(series)
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], BLUE));
+        
+        chart.draw_series(LineSeries::new(
+            trace.iter().map(|p| (p.iteration, p.best)),
+            RED.stroke_width(2),
+        ))?
+            .label("Best")
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], RED));
+        
+        chart.configure_series_labels().draw()?;
+        
+        root.present()?;
+        println!("  • Convergence chart created with {} samples", trace.len());
+        
+        Ok(())
+    }
+    
+    /// City markers with their names; unvisited cities are drawn in grey
+    fn draw_cities(&self, chart: &mut Chart<'_>, cities: &[String], positions: &[(f64, f64)], visited: &[bool]) -> Result<()> {
+        for (i, (x, y)) in positions.iter().enumerate() {
+            let color = if visited[i] { BLUE } else { SKIPPED };
             chart.draw_series(PointSeries::of_element(
                 vec![(*x, *y)],
                 10,
-                ShapeStyle::from(&BLUE).filled(),
+                ShapeStyle::from(&color).filled(),
                 &|coord, size, style| {
                     EmptyElement::at(coord) + Circle::new((0, 0), size, style)
                 },
@@ -52,28 +215,18 @@ impl Visualizer {
             chart.draw_series(std::iter::once(Text::new(
                 cities[i].clone(),
                 (*x, *y + 0.15),
-                ("Arial", 15).into_font(),
+                ("Arial", 15).into_font().color(if visited[i] { &BLACK } else { &SKIPPED }),
             )))?;
         }
         
-        // Gambar path
-        let mut path_points = Vec::new();
-        for &city_idx in path {
-            path_points.push(city_positions[city_idx]);
-        }
-        // Close loop
-        if !path.is_empty() {
-            path_points.push(city_positions[path[0]]);
-        }
-        
-        chart.draw_series(LineSeries::new(path_points.clone(), RED.stroke_width(3)))?
-            .label("Optimal Path")
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], RED));
-        
-        // Gambar arrow
-        for i in 0..path_points.len() - 1 {
-            let (x1, y1) = path_points[i];
-            let (x2, y2) = path_points[i + 1];
+        Ok(())
+    }
+    
+    /// Arrow heads three quarters along every segment of `points`
+    fn draw_arrows(&self, chart: &mut Chart<'_>, points: &[(f64, f64)], color: RGBAColor) -> Result<()> {
+        for i in 0..points.len() - 1 {
+            let (x1, y1) = points[i];
+            let (x2, y2) = points[i + 1];
             
             // Posisi arrow
             let arrow_x = x1 + 0.75 * (x2 - x1);
@@ -100,34 +253,16 @@ impl Visualizer {
                 
                 chart.draw_series(LineSeries::new(
                     vec![(arrow_x, arrow_y), (ax1, ay1)],
-                    RED.stroke_width(2),
+                    color.stroke_width(2),
                 ))?;
                 
                 chart.draw_series(LineSeries::new(
                     vec![(arrow_x, arrow_y), (ax2, ay2)],
-                    RED.stroke_width(2),
+                    color.stroke_width(2),
                 ))?;
             }
         }
         
-        chart.configure_series_labels().draw()?;
-        
-        // Path information
-        let path_text = format!(
-            "Path: {} → {}",
-            path.iter().map(|&i| cities[i].as_str()).collect::<Vec<_>>().join(" → "),
-            cities[path[0]]
-        );
-        
-        chart.draw_series(std::iter::once(Text::new(
-            path_text,
-            (-1.1, -1.1),
-            ("Arial", 12).into_font().color(&BLACK),
-        )))?;
-        
-        root.present()?;
-        println!("  • Visualization created with {} cities", cities.len());
-        
         Ok(())
     }
     